@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::token::Operator;
 
 #[derive(Debug, PartialEq, Clone, PartialOrd)]
@@ -10,29 +12,113 @@ pub enum PrimitiveTypes {
 
     // explicit types
     U64,
+    U32,
+    U16,
+    U8,
+    I64,
+    I32,
+    I16,
+    I8,
     F64,
     Bool,
+    Str, // pointer to the length, followed by the bytes
+    Array(Box<PrimitiveTypes>, usize), // (element type, number of elements)
+    Struct(String, usize), // (class name, size in bytes). The type checker sets the size
 
     // Only temporarely
     COUNT,
 }
 
+impl PrimitiveTypes {
+    // true for all explicit integer types
+    pub fn is_integer(&self) -> bool {
+        matches!(self,
+            PrimitiveTypes::U64 | PrimitiveTypes::U32 | PrimitiveTypes::U16 | PrimitiveTypes::U8 |
+            PrimitiveTypes::I64 | PrimitiveTypes::I32 | PrimitiveTypes::I16 | PrimitiveTypes::I8)
+    }
+
+    pub fn is_signed(&self) -> bool {
+        matches!(self, PrimitiveTypes::I64 | PrimitiveTypes::I32 | PrimitiveTypes::I16 | PrimitiveTypes::I8)
+    }
+
+    // Size in bytes when stored in memory. On the stack every value takes 8 bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            PrimitiveTypes::U8 | PrimitiveTypes::I8 => 1,
+            PrimitiveTypes::U16 | PrimitiveTypes::I16 => 2,
+            PrimitiveTypes::U32 | PrimitiveTypes::I32 => 4,
+            PrimitiveTypes::Array(element, len) => element.size() * len,
+            PrimitiveTypes::Struct(_, size) => *size,
+            _ => 8,
+        }
+    }
+
+    // Variables take whole 8 byte slots, so the next one is aligned again
+    pub fn slot_size(&self) -> usize {
+        self.size().div_ceil(8) * 8
+    }
+
+    // Smallest and biggest value of an integer type.
+    pub fn integer_range(&self) -> Option<(i128, i128)> {
+        if !self.is_integer() {
+            return None;
+        }
+        let bits = self.size() as u32 * 8;
+        if self.is_signed() {
+            Some((-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1))
+        } else {
+            Some((0, (1i128 << bits) - 1))
+        }
+    }
+}
+
+// The name of the type in the source, for diagnostics
+impl fmt::Display for PrimitiveTypes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrimitiveTypes::Number => write!(f, "number"),
+            PrimitiveTypes::Float => write!(f, "float"),
+            PrimitiveTypes::Integer => write!(f, "integer"),
+            PrimitiveTypes::Void => write!(f, "void"),
+            PrimitiveTypes::U64 => write!(f, "u64"),
+            PrimitiveTypes::U32 => write!(f, "u32"),
+            PrimitiveTypes::U16 => write!(f, "u16"),
+            PrimitiveTypes::U8 => write!(f, "u8"),
+            PrimitiveTypes::I64 => write!(f, "i64"),
+            PrimitiveTypes::I32 => write!(f, "i32"),
+            PrimitiveTypes::I16 => write!(f, "i16"),
+            PrimitiveTypes::I8 => write!(f, "i8"),
+            PrimitiveTypes::F64 => write!(f, "f64"),
+            PrimitiveTypes::Bool => write!(f, "bool"),
+            PrimitiveTypes::Str => write!(f, "str"),
+            PrimitiveTypes::Array(element, len) => write!(f, "{}[{}]", element, len),
+            PrimitiveTypes::Struct(name, _) => write!(f, "{}", name),
+            PrimitiveTypes::COUNT => write!(f, "COUNT"),
+        }
+    }
+}
+
 pub fn match_variable_type(typ: &str) -> Option<PrimitiveTypes> {
     match typ {
         "u64" => Some(PrimitiveTypes::U64),
+        "u32" => Some(PrimitiveTypes::U32),
+        "u16" => Some(PrimitiveTypes::U16),
+        "u8" => Some(PrimitiveTypes::U8),
+        "i64" => Some(PrimitiveTypes::I64),
+        "i32" => Some(PrimitiveTypes::I32),
+        "i16" => Some(PrimitiveTypes::I16),
+        "i8" => Some(PrimitiveTypes::I8),
         "f64" => Some(PrimitiveTypes::F64),
         "bool" => Some(PrimitiveTypes::Bool),
+        "str" => Some(PrimitiveTypes::Str),
         _ => None,
     }
 }
 
 pub fn match_return_type(typ: &str) -> Option<PrimitiveTypes> {
     match typ {
-        "u64" => Some(PrimitiveTypes::U64),
-        "f64" => Some(PrimitiveTypes::F64),
-        "bool" => Some(PrimitiveTypes::Bool),
         "void" => Some(PrimitiveTypes::Void),
-        _ => None,
+        _ => match_variable_type(typ),
     }
 }
 
@@ -43,6 +129,19 @@ pub struct ASTNode {
     pub loc: (usize, usize),
 }
 
+// '{x:.3}' prints 3 significant digits like python, '{x:.3f}' exactly 3 decimals
+#[derive(Debug, PartialEq, Clone)]
+pub struct FormatSpec {
+    pub precision: usize,
+    pub fixed: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Interpolation {
+    Text(String),
+    Value(ASTNode, Option<FormatSpec>),
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ConstLiteral {
     Float(String),
@@ -56,15 +155,32 @@ pub enum ASTNodeType {
     FunctionCall(String, Vec<ASTNode>, PrimitiveTypes),
     Assignment(String, Box<ASTNode>),
     BinaryOp(Box<ASTNode>, Operator, Box<ASTNode>, PrimitiveTypes),
+    UnaryOp(Operator, Box<ASTNode>, PrimitiveTypes),
+    Cast(Box<ASTNode>, PrimitiveTypes), // (value, target type)
+    ChainedComparison(Vec<ASTNode>, Vec<Operator>), // (operands, operators): 'a < b < c'
+    Conditional(Box<ASTNode>, Box<ASTNode>, Box<ASTNode>, PrimitiveTypes), // (condition, then, else, type): 'then if condition else else'
     Literal(PrimitiveTypes, String),
     Identifier(String, PrimitiveTypes),
-    BuiltinFunction(String, Box<ASTNode>),
+    BuiltinFunction(String, Vec<ASTNode>),
+    Len(Box<ASTNode>),
+    Index(Box<ASTNode>, Box<ASTNode>, PrimitiveTypes), // (array, index, element type): 'a[i]'
+    IndexAssignment(Box<ASTNode>, Box<ASTNode>, Box<ASTNode>), // (array, index, value): 'a[i] = value'
+    List(Vec<ASTNode>, PrimitiveTypes), // (elements, array type): '[1, 2, 3]', only to initialize arrays
+    Field(Box<ASTNode>, String, PrimitiveTypes, usize), // (instance, field, field type, offset): 'p.x'
+    FieldAssignment(Box<ASTNode>, Box<ASTNode>), // (field, value): 'p.x = value'
+    FString(Vec<Interpolation>), // only printed, never stored: there is no heap to build it in
     Declaration(String, PrimitiveTypes, Option<Box<ASTNode>>),
     Const(String, PrimitiveTypes, ConstLiteral),
+    Class(String, Vec<(String, PrimitiveTypes, (usize, usize))>), // (name, fields with their location)
     If(Box<ASTNode>, Vec<ASTNode>, Option<Vec<ASTNode>>),
     While(Box<ASTNode>, Vec<ASTNode>),
+    For(String, PrimitiveTypes, Box<ASTNode>, Box<ASTNode>, Box<ASTNode>, Vec<ASTNode>), // (var, var type, start, stop, step, body)
+    ForEach(String, PrimitiveTypes, Box<ASTNode>, Vec<ASTNode>), // (var, element type, array, body): 'for x in a'
+    Break,
+    Continue,
     SExpression(Box<ASTNode>), // used for standalone expr to clean up stack
     Return(Option<Box<ASTNode>>),
+    Error, // placeholder for a statement that failed to parse
 }
 
 impl ASTNode {
@@ -76,10 +192,57 @@ impl ASTNode {
     pub fn get_type(&self) -> Result<PrimitiveTypes, String> {
         match &self.node_type {
             ASTNodeType::BinaryOp( _, _, _, typ) => Ok(typ.clone()),
+            ASTNodeType::UnaryOp(_, _, typ) => Ok(typ.clone()),
+            ASTNodeType::Cast(_, typ) => Ok(typ.clone()),
+            ASTNodeType::Conditional(_, _, _, typ) => Ok(typ.clone()),
+            ASTNodeType::ChainedComparison(_, _) => Ok(PrimitiveTypes::Bool),
+            ASTNodeType::Len(_) => Ok(PrimitiveTypes::U64),
+            ASTNodeType::Index(_, _, typ) => Ok(typ.clone()),
+            ASTNodeType::List(_, typ) => Ok(typ.clone()),
+            ASTNodeType::Field(_, _, typ, _) => Ok(typ.clone()),
+            ASTNodeType::FString(_) => Ok(PrimitiveTypes::Str),
             ASTNodeType::Literal(typ, _) => Ok(typ.clone()),
             ASTNodeType::Identifier(_, typ) => Ok(typ.clone()),
             ASTNodeType::FunctionCall(_, _, return_type) => Ok(return_type.clone()),
             _ => Err(format!("Tried to access type of typeless node: {:#?}", self))
         }
     }
+
+    // Calls are the only expressions with side effects
+    pub fn has_call(&self) -> bool {
+        match &self.node_type {
+            ASTNodeType::FunctionCall(_, _, _) => true,
+            ASTNodeType::BinaryOp(left, _, right, _) => left.has_call() || right.has_call(),
+            ASTNodeType::UnaryOp(_, operand, _) => operand.has_call(),
+            ASTNodeType::Cast(value, _) => value.has_call(),
+            ASTNodeType::Conditional(cond, then, els, _) => cond.has_call() || then.has_call() || els.has_call(),
+            ASTNodeType::ChainedComparison(operands, _) => operands.iter().any(ASTNode::has_call),
+            ASTNodeType::Len(value) => value.has_call(),
+            ASTNodeType::Index(array, index, _) => array.has_call() || index.has_call(),
+            ASTNodeType::List(elements, _) => elements.iter().any(ASTNode::has_call),
+            ASTNodeType::Field(instance, _, _, _) => instance.has_call(),
+            ASTNodeType::FString(parts) => parts.iter().any(|part| matches!(part, Interpolation::Value(value, _) if value.has_call())),
+            ASTNodeType::Literal(_, _) |
+            ASTNodeType::Identifier(_, _) => false,
+
+            // statements
+            ASTNodeType::FunctionDef(_, _, _, _) |
+            ASTNodeType::Assignment(_, _) |
+            ASTNodeType::BuiltinFunction(_, _) |
+            ASTNodeType::IndexAssignment(_, _, _) |
+            ASTNodeType::FieldAssignment(_, _) |
+            ASTNodeType::Declaration(_, _, _) |
+            ASTNodeType::Const(_, _, _) |
+            ASTNodeType::Class(_, _) |
+            ASTNodeType::If(_, _, _) |
+            ASTNodeType::While(_, _) |
+            ASTNodeType::For(_, _, _, _, _, _) |
+            ASTNodeType::ForEach(_, _, _, _) |
+            ASTNodeType::Break |
+            ASTNodeType::Continue |
+            ASTNodeType::SExpression(_) |
+            ASTNodeType::Return(_) |
+            ASTNodeType::Error => false,
+        }
+    }
 }
@@ -2,16 +2,18 @@ use std::collections::HashMap;
 use std::ops::{Deref, DerefMut};
 use std::rc::Rc;
 
-use crate::os::systemv::{SystemV, Parameter};
+use crate::os::systemv::{Local, SystemV, Parameter};
 use crate::token::Operator;
 use crate::operations::{ConstVariable, Operation, OperationsType, Program};
-use crate::ast::{ASTNode, ASTNodeType, PrimitiveTypes};
+use crate::ast::{ASTNode, ASTNodeType, FormatSpec, Interpolation, PrimitiveTypes};
+use crate::diagnostic::{Diagnostic, Span};
 
 
 #[derive(Debug, Clone)]
 enum VarriableType {
   Global(String, PrimitiveTypes),
   Parameter(Parameter),
+  Local(Local),
   Const(ConstVariable),
 }
 
@@ -83,6 +85,7 @@ pub struct Builder {
   vars: Vec<String>,
   consts: Vec<ConstVariable>,
   ref_count: usize,
+  loops: Vec<usize>, // ref numbers of the enclosing while loops, innermost last
   file_name: String,
 }
 
@@ -100,29 +103,21 @@ impl  Builder {
       vars: Vec::new(),
       consts: Vec::new(),
       ref_count: 0,
+      loops: Vec::new(),
       file_name,
     }
   }
 
-  pub fn panic_loc<T>(&self, node: &ASTNode, msg: &str) -> T {
-    let mut fmt: String = String::new();
-    let (row, col) = node.get_loc();
-    fmt.push_str(&self.file_name);
-    fmt.push(':');
-    fmt.push_str(&row.to_string());
-    fmt.push(':');
-    fmt.push_str(&col.to_string());
-    fmt.push('\n');
-    eprintln!("{}", fmt);
-    panic!("{}", msg);
+  fn error(&self, node: &ASTNode, msg: impl Into<String>) -> Diagnostic {
+    Diagnostic::error(&self.file_name, Span::at(*node.get_loc()), msg)
   }
 
-  pub fn build_program(&mut self,ast: &mut Vec<ASTNode>) -> Program {
+  pub fn build_program(&mut self,ast: &mut Vec<ASTNode>) -> Result<Program, Diagnostic> {
     let mut program: Program = Program::new();
-    self.scan_nodes(ast);
-    // self.translate_nodes(ast, &mut program, ScopeType::Root);
-    self.translate_nodes(ast, &mut program);
-    program
+    self.scan_nodes(ast)?;
+    // self.translate_nodes(ast, &mut program, ScopeType::Root)?;
+    self.translate_nodes(ast, &mut program)?;
+    Ok(program)
   }
 
   fn get_ref_number(&mut self) -> usize {
@@ -131,28 +126,47 @@ impl  Builder {
     n
   }
 
-  fn delcare_global_var(&mut self,node: &ASTNode, name: &String, value_type: PrimitiveTypes) {
+  fn delcare_global_var(&mut self,node: &ASTNode, name: &String, value_type: PrimitiveTypes) -> Result<(), Diagnostic> {
     if self.scopes.is_empty() {
-      self.panic_loc(node, "Scope is emppty!")
+      return Err(self.error(node, "Scope is emppty!"))
     }
     for key in self.functions.keys() {
       if key.deref() == name {
-        self.panic_loc(node, format!("var '{}' is trying to shadow a function. this is not allowed.", name).as_str())
+        return Err(self.error(node, format!("var '{}' is trying to shadow a function. this is not allowed.", name)))
       }
     }
 
     let var = VarriableType::Global(name.clone(), value_type.clone());
     self.scopes.last_mut().unwrap().insert(Rc::new(name.to_string()), var);
+    Ok(())
   }
 
-  fn declare_const(&mut self, node: &ASTNode, progrma: &mut Program) {
+  fn declare_local_var(&mut self, node: &ASTNode, name: &String, function: &Rc<String>) -> Result<Local, Diagnostic> {
+    if self.scopes.is_empty() {
+      return Err(self.error(node, "Scope is emppty!"))
+    }
+    for key in self.functions.keys() {
+      if key.deref() == name {
+        return Err(self.error(node, format!("var '{}' is trying to shadow a function. this is not allowed.", name)))
+      }
+    }
+
+    let Some(local) = self.functions.get(function).and_then(|f| f.get_local(name)) else {
+      return Err(self.error(node, format!("No stack slot was reserved for '{}' in function '{}'", name, function)))
+    };
+    let local = local.clone();
+    self.scopes.last_mut().unwrap().insert(Rc::new(name.to_string()), VarriableType::Local(local.clone()));
+    Ok(local)
+  }
+
+  fn declare_const(&mut self, node: &ASTNode, progrma: &mut Program) -> Result<(), Diagnostic> {
     let ASTNodeType::Const(ref name, ref const_type, ref value) = node.node_type else {
-      self.panic_loc(node, &format!("Tried to declare const, got this instaead {:?}", node))
+      return Err(self.error(node, format!("Tried to declare const, got this instaead {:?}", node)))
     };
 
     for key in self.functions.keys() {
       if key.deref() == name {
-        self.panic_loc(node, format!("const '{}' is trying to shadow a function. this is not allowed.", name).as_str())
+        return Err(self.error(node, format!("const '{}' is trying to shadow a function. this is not allowed.", name)))
       }
     }
 
@@ -161,6 +175,7 @@ impl  Builder {
     self.consts.push(const_.clone());
     progrma.consts.push(const_.clone());
     self.scopes.last_mut().unwrap().insert(Rc::new(name.clone()), VarriableType::Const(const_));
+    Ok(())
   }
 
   fn get_var(&self, name: &String) -> Option<VarriableType> {
@@ -172,20 +187,21 @@ impl  Builder {
     None
   }
 
-  fn scan_nodes(&mut self, nodes: &Vec<ASTNode>) {
+  fn scan_nodes(&mut self, nodes: &Vec<ASTNode>) -> Result<(), Diagnostic> {
     // self.scopes.push(Scope { scope_type, name: HashMap::new() });
     for node in nodes {
-      self.scan_node(node);
+      self.scan_node(node)?;
     }
     self.scopes.pop();
+    Ok(())
   }
 
-  fn scan_node(&mut self, node: &ASTNode) {
+  fn scan_node(&mut self, node: &ASTNode) -> Result<(), Diagnostic> {
     match node.node_type {
-        ASTNodeType::FunctionDef(ref name, ref args, ref return_type, _) => {
+        ASTNodeType::FunctionDef(ref name, ref args, ref return_type, ref body) => {
           for key in self.functions.keys() {
             if key.deref() == name {
-              self.panic_loc(node, format!("Duplicate function with name '{}'.", name).as_str())
+              return Err(self.error(node, format!("Duplicate function with name '{}'.", name)))
             }
           }
 
@@ -194,6 +210,7 @@ impl  Builder {
           if let Some(args) = args {
             parameters.add_parameters(args);
           };
+          Builder::scan_locals(&mut parameters, body);
           parameters.add_return(return_type.clone());
           self.functions.insert(func_name, parameters);
         },
@@ -202,18 +219,85 @@ impl  Builder {
         ASTNodeType::Const(_, _, _) |
         ASTNodeType::Assignment(_, _) |
         ASTNodeType::BinaryOp(_, _, _, _) |
+        ASTNodeType::UnaryOp(_, _, _) |
+        ASTNodeType::Cast(_, _) |
+        ASTNodeType::Conditional(_, _, _, _) |
+        ASTNodeType::ChainedComparison(_, _) |
         ASTNodeType::Literal(_, _) |
         ASTNodeType::Identifier(_, _) |
         ASTNodeType::BuiltinFunction(_, _) |
+        ASTNodeType::Len(_) |
+        ASTNodeType::FString(_) |
+        ASTNodeType::Index(_, _, _) |
+        ASTNodeType::IndexAssignment(_, _, _) |
+        ASTNodeType::List(_, _) |
+        ASTNodeType::Field(_, _, _, _) |
+        ASTNodeType::FieldAssignment(_, _) |
+        ASTNodeType::Class(_, _) |
         ASTNodeType::If(_, _, _) |
         ASTNodeType::While(_, _) |
+        ASTNodeType::For(_, _, _, _, _, _) |
+        ASTNodeType::ForEach(_, _, _, _) |
+        ASTNodeType::Break |
+        ASTNodeType::Continue |
         ASTNodeType::SExpression(_) |
         ASTNodeType::Return(_) |
-        ASTNodeType::FunctionCall(_, _, _) => {},
+        ASTNodeType::FunctionCall(_, _, _) |
+        ASTNodeType::Error => {},
+    }
+    Ok(())
+  }
+
+  // Every declaration inside a function gets its own slot in the stack frame.
+  // The type checker already gave shadowed variables unique names.
+  fn scan_locals(function: &mut SystemV, nodes: &Vec<ASTNode>) {
+    for node in nodes {
+      match node.node_type {
+        ASTNodeType::Declaration(ref name, ref value_type, _) => function.add_local(name, value_type),
+        ASTNodeType::If(_, ref then, ref els) => {
+          Builder::scan_locals(function, then);
+          if let Some(els) = els {
+            Builder::scan_locals(function, els);
+          }
+        }
+        ASTNodeType::While(_, ref body) => Builder::scan_locals(function, body),
+        ASTNodeType::For(ref name, ref var_type, _, _, ref step, ref body) => {
+          let (stop, step_name) = Builder::range_var_names(name);
+          function.add_local(name, var_type);
+          function.add_local(&stop, var_type);
+          function.add_local(&step_name, &step.get_type().unwrap_or(PrimitiveTypes::I64));
+          Builder::scan_locals(function, body);
+        }
+        ASTNodeType::ForEach(ref name, ref element_type, _, ref body) => {
+          function.add_local(name, element_type);
+          function.add_local(&Builder::element_index_name(name), &PrimitiveTypes::U64);
+          Builder::scan_locals(function, body);
+        }
+        _ => {}
+      }
     }
   }
 
-  fn translate_nodes(&mut self, nodes: &Vec<ASTNode>, program: &mut Program) {
+  // Hidden variables that keep stop and step of a range, so they are only evaluated once.
+  // '.' can not be part of an identifier, so they never clash with user variables.
+  fn range_var_names(name: &str) -> (String, String) {
+    (format!("{name}.stop"), format!("{name}.step"))
+  }
+
+  // Hidden variable with the position of the current element, like the ones of a range
+  fn element_index_name(name: &str) -> String {
+    format!("{name}.index")
+  }
+
+  // Values on the stack are 64 bit. Results of smaller integers have to be cut back, so they
+  // wrap around like the type would (e.g. 255 + 1 for u8).
+  fn truncate(typ: &PrimitiveTypes, program: &mut Program) {
+    if typ.is_integer() && typ.size() < 8 {
+      program.push(Operation::Truncate(typ.clone()));
+    }
+  }
+
+  fn translate_nodes(&mut self, nodes: &Vec<ASTNode>, program: &mut Program) -> Result<(), Diagnostic> {
     // match scope_type {
     //     ScopeType::Root => program.cureent_target = OperationsType::Main,
     //     ScopeType::Function(_) => program.cureent_target = ,
@@ -221,146 +305,461 @@ impl  Builder {
     // };
     self.scopes.push(Scope::new());
     for node in nodes {
-      self.translate_node(node, program);
+      self.translate_node(node, program)?;
     }
     self.scopes.pop();
+    Ok(())
+  }
+
+  // Pushes the address of 'array[index]' and returns the element type. Indices outside of the
+  // array end the program.
+  fn translate_element_address(&mut self, array: &ASTNode, index: &ASTNode, program: &mut Program) -> Result<PrimitiveTypes, Diagnostic> {
+    let ASTNodeType::Identifier(ref name, PrimitiveTypes::Array(ref element, len)) = array.node_type else {
+      return Err(self.error(array, "Only arrays can be indexed"))
+    };
+    match self.get_var(name) {
+      Some(VarriableType::Global(name, _)) => program.push(Operation::PushAddress(name)),
+      Some(VarriableType::Local(l)) => l.translate_address(program),
+      Some(VarriableType::Parameter(_)) |
+      Some(VarriableType::Const(_)) => return Err(self.error(array, format!("'{}' is not an array", name))),
+      None => return Err(self.error(array, format!("'{}' was not declared!", name))),
+    }
+    self.translate_node(index, program)?;
+    program.push(Operation::ElementAddress(element.size(), len));
+    Ok(element.deref().clone())
   }
 
-  fn translate_node(&mut self, node: &ASTNode, program: &mut Program) {
+  // Pushes the address of the field 'instance.field' and returns its type
+  fn translate_field_address(&mut self, field: &ASTNode, program: &mut Program) -> Result<PrimitiveTypes, Diagnostic> {
+    let ASTNodeType::Field(ref instance, _, ref field_type, offset) = field.node_type else {
+      return Err(self.error(field, "Expected a field of an instance"))
+    };
+    let ASTNodeType::Identifier(ref name, PrimitiveTypes::Struct(_, _)) = instance.node_type else {
+      return Err(self.error(instance, "Only instances of classes have fields"))
+    };
+    match self.get_var(name) {
+      Some(VarriableType::Global(name, _)) => program.push(Operation::PushAddress(name)),
+      Some(VarriableType::Local(l)) => l.translate_address(program),
+      Some(VarriableType::Parameter(_)) |
+      Some(VarriableType::Const(_)) => return Err(self.error(instance, format!("'{}' is not an instance of a class", name))),
+      None => return Err(self.error(instance, format!("'{}' was not declared!", name))),
+    }
+    program.push(Operation::FieldAddress(offset));
+    Ok(field_type.clone())
+  }
+
+  // 'var a: u64[3] = [1, 2, 3]' stores the values one by one, like 'a[0] = 1'
+  fn translate_list(&mut self, name: &str, array_type: &PrimitiveTypes, elements: &[ASTNode], program: &mut Program) -> Result<(), Diagnostic> {
+    for (i, element) in elements.iter().enumerate() {
+      let loc = element.loc;
+      let expr = |node_type| Box::new(ASTNode { node_type, loc });
+      let array = expr(ASTNodeType::Identifier(name.to_string(), array_type.clone()));
+      let index = expr(ASTNodeType::Literal(PrimitiveTypes::U64, i.to_string()));
+      let assignment = expr(ASTNodeType::IndexAssignment(array, index, Box::new(element.clone())));
+      self.translate_node(&assignment, program)?;
+    }
+    Ok(())
+  }
+
+  // An f-string is printed piece by piece, the text parts are string literals
+  fn translate_interpolations(&mut self, parts: &[Interpolation], program: &mut Program) -> Result<(), Diagnostic> {
+    for part in parts {
+      match part {
+        Interpolation::Text(text) => {
+          program.strings.push(text.clone());
+          program.push(Operation::PushStr(program.strings.len() - 1));
+          program.push(Operation::PrintStr);
+        }
+        Interpolation::Value(value, spec) => self.translate_print(value, spec.as_ref(), program)?,
+      }
+    }
+    Ok(())
+  }
+
+  fn translate_print(&mut self, expr: &ASTNode, spec: Option<&FormatSpec>, program: &mut Program) -> Result<(), Diagnostic> {
+    self.translate_node(expr, program)?;
+    let expr_type = expr.get_type().map_err(|msg| self.error(expr, msg))?;
+    let operation = match (self.print_operation(expr, &expr_type)?, spec) {
+      (Operation::PrintFloat(_, _), Some(spec)) if spec.fixed => Operation::PrintFloat(spec.precision, false),
+      (Operation::PrintFloat(_, _), Some(spec)) => Operation::PrintFloatGeneral(spec.precision),
+      (operation, _) => operation,
+    };
+    program.push(operation);
+    Ok(())
+  }
+
+  // Prints the value on top of the stack, without a terminator
+  fn print_operation(&self, node: &ASTNode, typ: &PrimitiveTypes) -> Result<Operation, Diagnostic> {
+    match typ {
+      PrimitiveTypes::U64 |
+      PrimitiveTypes::U32 |
+      PrimitiveTypes::U16 |
+      PrimitiveTypes::U8 => Ok(Operation::PrintInt),
+      PrimitiveTypes::I64 |
+      PrimitiveTypes::I32 |
+      PrimitiveTypes::I16 |
+      PrimitiveTypes::I8 => Ok(Operation::PrintSignedInt),
+      PrimitiveTypes::F64 => Ok(Operation::PrintFloat(6, true)),
+      PrimitiveTypes::Bool => Ok(Operation::PrintBool),
+      PrimitiveTypes::Str => Ok(Operation::PrintStr),
+      PrimitiveTypes::Number |
+      PrimitiveTypes::Float |
+      PrimitiveTypes::Integer |
+      PrimitiveTypes::Void |
+      PrimitiveTypes::Array(_, _) |
+      PrimitiveTypes::Struct(_, _) |
+      PrimitiveTypes::COUNT => Err(self.error(node, format!("Can not print a value of type '{}'", typ))),
+    }
+  }
+
+  // The operation for 'left op right' where the result has the type 'typ'
+  fn binary_operation(&self, node: &ASTNode, typ: &PrimitiveTypes, op: &Operator, left_t: &PrimitiveTypes, right_t: &PrimitiveTypes) -> Result<Operation, Diagnostic> {
+    let operation = match (typ, op, left_t, right_t) {
+        (int_type, Operator::Plus, _, _) if int_type.is_integer() => Operation::AddInt,
+        (int_type, Operator::Minus, _, _) if int_type.is_integer() => Operation::MinusInt,
+        (int_type, Operator::Mul, _, _) if int_type.is_signed() => Operation::MultSignedInt,
+        (int_type, Operator::Mul, _, _) if int_type.is_integer() => Operation::MultInt,
+        (int_type, Operator::Div, _, _) if int_type.is_signed() => Operation::DivSignedInt,
+        (int_type, Operator::Div, _, _) if int_type.is_integer() => Operation::DivInt,
+        (int_type, Operator::FloorDiv, _, _) if int_type.is_signed() => Operation::FloorDivSignedInt,
+        (int_type, Operator::FloorDiv, _, _) if int_type.is_integer() => Operation::DivInt,
+        (int_type, Operator::Mod, _, _) if int_type.is_signed() => Operation::ModSignedInt,
+        (int_type, Operator::Mod, _, _) if int_type.is_integer() => Operation::ModInt,
+        (int_type, Operator::Power, _, _) if int_type.is_signed() => Operation::PowerSignedInt,
+        (int_type, Operator::Power, _, _) if int_type.is_integer() => Operation::PowerInt,
+        (int_type, Operator::BitAnd, _, _) if int_type.is_integer() => Operation::BitAndInt,
+        (int_type, Operator::BitOr, _, _) if int_type.is_integer() => Operation::BitOrInt,
+        (int_type, Operator::BitXor, _, _) if int_type.is_integer() => Operation::BitXorInt,
+        (int_type, Operator::ShiftLeft, _, _) if int_type.is_integer() => Operation::ShiftLeftInt,
+        (int_type, Operator::ShiftRight, _, _) if int_type.is_signed() => Operation::ShiftRightSignedInt,
+        (int_type, Operator::ShiftRight, _, _) if int_type.is_integer() => Operation::ShiftRightInt,
+        (int_type, op, _, _) if int_type.is_integer() => return Err(self.error(node, format!("Type '{}' is not defined for '{:?}'", int_type, op))),
+        (PrimitiveTypes::F64, Operator::Plus, _, _) => Operation::AddFloat,
+        (PrimitiveTypes::F64, Operator::Minus, _, _) => Operation::MinusFloat,
+        (PrimitiveTypes::F64, Operator::Mul, _, _) => Operation::MultFloat,
+        (PrimitiveTypes::F64, Operator::Div, _, _) => Operation::DivFloat,
+        (PrimitiveTypes::F64, Operator::FloorDiv, _, _) => Operation::FloorDivFloat,
+        (PrimitiveTypes::F64, Operator::Mod, _, _) => Operation::ModFloat,
+        (PrimitiveTypes::F64, Operator::Power, _, _) => Operation::PowerFloat,
+        (PrimitiveTypes::F64, op, _, _) => return Err(self.error(node, format!("Type 'f64' is not defined for '{:#?}", op))),
+        (PrimitiveTypes::Bool, Operator::Equal, int_type, _) if int_type.is_integer() => Operation::EqualInt,
+        (PrimitiveTypes::Bool, Operator::NotEqual, int_type, _) if int_type.is_integer() => Operation::NotEqualInt,
+        (PrimitiveTypes::Bool, Operator::Less, int_type, _) if int_type.is_signed() => Operation::LessInt,
+        (PrimitiveTypes::Bool, Operator::LessEqual, int_type, _) if int_type.is_signed() => Operation::LessEqualInt,
+        (PrimitiveTypes::Bool, Operator::Greater, int_type, _) if int_type.is_signed() => Operation::GreaterInt,
+        (PrimitiveTypes::Bool, Operator::GreaterEqual, int_type, _) if int_type.is_signed() => Operation::GreaterEqualInt,
+        (PrimitiveTypes::Bool, Operator::Less, int_type, _) if int_type.is_integer() => Operation::BelowInt,
+        (PrimitiveTypes::Bool, Operator::LessEqual, int_type, _) if int_type.is_integer() => Operation::BelowEqualInt,
+        (PrimitiveTypes::Bool, Operator::Greater, int_type, _) if int_type.is_integer() => Operation::AboveInt,
+        (PrimitiveTypes::Bool, Operator::GreaterEqual, int_type, _) if int_type.is_integer() => Operation::AboveEqualInt,
+        (PrimitiveTypes::Bool, Operator::Less, PrimitiveTypes::F64, PrimitiveTypes::F64) => Operation::LessFloat,
+        (PrimitiveTypes::Bool, Operator::LessEqual, PrimitiveTypes::F64, PrimitiveTypes::F64) => Operation::LessEqualFloat,
+        (PrimitiveTypes::Bool, Operator::Equal, PrimitiveTypes::F64, PrimitiveTypes::F64) => Operation::EqualFloat,
+        (PrimitiveTypes::Bool, Operator::NotEqual, PrimitiveTypes::F64, PrimitiveTypes::F64) => Operation::NotEqualFloat,
+        (PrimitiveTypes::Bool, Operator::Greater, PrimitiveTypes::F64, PrimitiveTypes::F64) => Operation::GreaterFloat,
+        (PrimitiveTypes::Bool, Operator::GreaterEqual, PrimitiveTypes::F64, PrimitiveTypes::F64) => Operation::GreaterEqualFloat,
+        // bools are 0 or 1, so they compare like integers
+        (PrimitiveTypes::Bool, Operator::Equal, PrimitiveTypes::Bool, PrimitiveTypes::Bool) => Operation::EqualInt,
+        (PrimitiveTypes::Bool, Operator::NotEqual, PrimitiveTypes::Bool, PrimitiveTypes::Bool) => Operation::NotEqualInt,
+        (PrimitiveTypes::Bool, Operator::Is, PrimitiveTypes::Bool, PrimitiveTypes::Bool) => Operation::EqualInt,
+        (PrimitiveTypes::Bool, Operator::IsNot, PrimitiveTypes::Bool, PrimitiveTypes::Bool) => Operation::NotEqualInt,
+        (PrimitiveTypes::Bool, op, _, _) => return Err(self.error(node, format!("Type 'bool' is not defined for '{:#?}", op))),
+
+        // ambiguous types
+        (PrimitiveTypes::Number, _, _, _) => return Err(self.error( node, "Ambigupus type 'Number'")),
+        (PrimitiveTypes::Float,_, _, _) => return Err(self.error(node, "Ambigupus type 'float'")),
+        (PrimitiveTypes::Integer, _, _, _) => return Err(self.error(node, "Ambigupus type 'integer'")),
+
+        // invalid types
+        (PrimitiveTypes::Void, _, _, _) => return Err(self.error(node, "Operations not defined for 'void'")),
+        (_, _, _, _) => return Err(self.error(node, "Invalid type at BinaryOp translation!")),
+    };
+    Ok(operation)
+  }
+
+  fn translate_node(&mut self, node: &ASTNode, program: &mut Program) -> Result<(), Diagnostic> {
     // let scope = self.scopes.last_mut().unwrap();
     // let mut operations = &mut self.scopes.last_mut().unwrap().target.clone();
     match node.node_type {
       ASTNodeType::Assignment(ref name, ref value) => {
         let Some(typ) = self.get_var(name) else {
-          self.panic_loc(node, format!("'{}' was not declared!", name).as_str())
+          return Err(self.error(node, format!("'{}' was not declared!", name)))
         };
         match typ {
           VarriableType::Global(name, typ) => {
             match typ {
-              PrimitiveTypes::U64 => {
-                self.translate_node(value, program);
-                program.push(Operation::StoreInt(name));
-              },
               PrimitiveTypes::F64 => {
-                self.translate_node(value, program);
+                self.translate_node(value, program)?;
                 program.push(Operation::StoreFloat(name));
               }
-              _ => self.panic_loc(node, "Unexpected type!"),
+              typ if typ.is_integer() || typ == PrimitiveTypes::Bool || typ == PrimitiveTypes::Str => {
+                self.translate_node(value, program)?;
+                program.push(Operation::StoreInt(name, typ));
+              },
+              _ => return Err(self.error(node, "Unexpected type!")),
             }
           }
           VarriableType::Parameter(p) => {
-            self.translate_node(value, program);
+            self.translate_node(value, program)?;
             p.translate_store(program);
           }
+          VarriableType::Local(l) => {
+            self.translate_node(value, program)?;
+            l.translate_store(program);
+          }
           VarriableType::Const(ConstVariable(ref name, _, _)) => {
-            self.panic_loc(node, &format!("Trying to assign a value to a const '{name}', which is not allowed."))
+            return Err(self.error(node, format!("Trying to assign a value to a const '{name}', which is not allowed.")))
           }
         }
       }
+      // The right side of 'and' and 'or' is skipped when the left one already decides the result.
+      // The left value stays on the stack as the result in that case.
+      ASTNodeType::BinaryOp(ref left, ref op @ Operator::And, ref right, _) |
+      ASTNodeType::BinaryOp(ref left, ref op @ Operator::Or, ref right, _) => {
+        let n = self.get_ref_number();
+        self.translate_node(left, program)?;
+        if *op == Operator::And {
+          program.push(Operation::ShortCircuitAnd(n));
+        } else {
+          program.push(Operation::ShortCircuitOr(n));
+        }
+        self.translate_node(right, program)?;
+        program.push(Operation::EndShortCircuit(n));
+      }
       ASTNodeType::BinaryOp(ref left, ref op, ref right, ref typ) => {
-        self.translate_node(left, program);
-        self.translate_node(right, program);
+        self.translate_node(left, program)?;
+        self.translate_node(right, program)?;
         let left_t = left.get_type().unwrap();
-        let right_t = left.get_type().unwrap();
-        let operation = match (typ, op, left_t, right_t) {
-            (PrimitiveTypes::U64,Operator::Plus, _, _) => Operation::AddInt,
-            (PrimitiveTypes::U64,Operator::Minus, _, _) => Operation::MinusInt,
-            (PrimitiveTypes::U64,Operator::Mul, _, _) => Operation::MultInt,
-            (PrimitiveTypes::U64,Operator::Div, _, _) => Operation::DivInt,
-            (PrimitiveTypes::U64, op, _, _) => self.panic_loc(node, format!("Type 'u64' is not defined for '{:#?}", op).as_str()),
-            (PrimitiveTypes::F64, Operator::Plus, _, _) => Operation::AddFloat,
-            (PrimitiveTypes::F64, Operator::Minus, _, _) => Operation::MinusFloat,
-            (PrimitiveTypes::F64, Operator::Mul, _, _) => Operation::MultFloat,
-            (PrimitiveTypes::F64, Operator::Div, _, _) => Operation::DivFloat,
-            (PrimitiveTypes::F64, op, _, _) => self.panic_loc(node, format!("Type 'f64' is not defined for '{:#?}", op).as_str()),
-            (PrimitiveTypes::Bool, Operator::And, PrimitiveTypes::Bool, PrimitiveTypes::Bool) => Operation::AndBool,
-            (PrimitiveTypes::Bool, Operator::Or, PrimitiveTypes::Bool, PrimitiveTypes::Bool) => Operation::OrBool,
-            (PrimitiveTypes::Bool, Operator::Less, PrimitiveTypes::U64, PrimitiveTypes::U64) => Operation::LessInt,
-            (PrimitiveTypes::Bool, Operator::Equal, PrimitiveTypes::U64, PrimitiveTypes::U64) => Operation::EqualInt,
-            (PrimitiveTypes::Bool, Operator::Greater, PrimitiveTypes::U64, PrimitiveTypes::U64) => Operation::GreaterInt,
-            (PrimitiveTypes::Bool, op, _, _) => self.panic_loc(node, format!("Type 'bool' is not defined for '{:#?}", op).as_str()),
-
-            // ambiguous types
-            (PrimitiveTypes::Number, _, _, _) => self.panic_loc( node, "Ambigupus type 'Number'"),
-            (PrimitiveTypes::Float,_, _, _) => self.panic_loc(node, "Ambigupus type 'Float'"),
-            (PrimitiveTypes::Integer, _, _, _) => self.panic_loc(node, "Ambigupus type 'Integer'"),
-
-            // invalid types
-            (PrimitiveTypes::Void, _, _, _) => self.panic_loc(node, "Operations not defined for 'void'"),
-            (PrimitiveTypes::COUNT,_, _, _) => self.panic_loc(node, "Invalid type at BinaryOp translation!"),
+        let right_t = right.get_type().unwrap();
+        let operation = self.binary_operation(node, typ, op, &left_t, &right_t)?;
+        program.push(operation);
+        Builder::truncate(typ, program);
+      }
+      ASTNodeType::UnaryOp(ref op, ref operand, ref typ) => {
+        self.translate_node(operand, program)?;
+        let operation = match (typ, op) {
+          (PrimitiveTypes::F64, Operator::Minus) => Operation::NegFloat,
+          (int_type, Operator::Minus) if int_type.is_signed() => Operation::NegInt,
+          (int_type, Operator::BitNot) if int_type.is_integer() => Operation::BitNotInt,
+          (PrimitiveTypes::Bool, Operator::Not) => Operation::NotBool,
+          (typ, op) => return Err(self.error(node, format!("Type '{}' is not defined for unary '{:?}'", typ, op))),
         };
         program.push(operation);
+        Builder::truncate(typ, program);
+      }
+      ASTNodeType::Cast(ref value, ref target) => {
+        self.translate_node(value, program)?;
+        let value_type = value.get_type().map_err(|msg| self.error(value, msg))?;
+        match (&value_type, target) {
+          (value_type, target) if value_type == target => {}
+          // anything that is not zero is true
+          (int_type, PrimitiveTypes::Bool) if int_type.is_integer() => {
+            program.push(Operation::PushInt("0".to_string()));
+            program.push(Operation::NotEqualInt);
+          }
+          (PrimitiveTypes::F64, PrimitiveTypes::Bool) => {
+            program.push(Operation::PushFloat("0.0".to_string()));
+            program.push(Operation::NotEqualFloat);
+          }
+          (PrimitiveTypes::Bool, int_type) if int_type.is_integer() => {}
+          (from, int_type) if from.is_integer() && int_type.is_integer() => Builder::truncate(int_type, program),
+          (PrimitiveTypes::U64, PrimitiveTypes::F64) => program.push(Operation::UnsignedIntToFloat),
+          (_, PrimitiveTypes::F64) => program.push(Operation::IntToFloat),
+          (PrimitiveTypes::F64, PrimitiveTypes::U64) => program.push(Operation::FloatToUnsignedInt),
+          (PrimitiveTypes::F64, int_type) if int_type.is_integer() => {
+            program.push(Operation::FloatToInt);
+            Builder::truncate(int_type, program);
+          }
+          (from, to) => return Err(self.error(node, format!("Can not cast '{}' to '{}'", from, to))),
+        }
+      }
+      // 'a < b < c' is 'a < b and b < c' with 'b' evaluated once. Every comparison but the last keeps
+      // its right operand on the stack for the next one. The first false one jumps to the end.
+      ASTNodeType::ChainedComparison(ref operands, ref ops) => {
+        let n = self.get_ref_number();
+        let operand_type = operands[0].get_type().map_err(|msg| self.error(node, msg))?;
+        self.translate_node(&operands[0], program)?;
+        for (i, op) in ops.iter().enumerate() {
+          self.translate_node(&operands[i + 1], program)?;
+          let last = i + 1 == ops.len();
+          if !last {
+            program.push(Operation::KeepComparand);
+          }
+          program.push(self.binary_operation(node, &PrimitiveTypes::Bool, op, &operand_type, &operand_type)?);
+          if !last {
+            program.push(Operation::ChainComparison(n));
+          }
+        }
+        program.push(Operation::EndChainComparison(n));
+      }
+      // only the chosen branch is evaluated, it leaves its value on the stack
+      ASTNodeType::Conditional(ref cond, ref then, ref els, _) => {
+        self.translate_node(cond, program)?;
+        let n = self.get_ref_number();
+        program.push(Operation::If(n));
+        self.translate_node(then, program)?;
+        program.push(Operation::Else(n));
+        self.translate_node(els, program)?;
+        program.push(Operation::EndIF(n));
       }
       ASTNodeType::Literal(ref typ, ref symbols) => {
         match typ {
-          PrimitiveTypes::U64 => program.push(Operation::PushInt(symbols.clone())),
+          PrimitiveTypes::U64 |
+          PrimitiveTypes::U32 |
+          PrimitiveTypes::U16 |
+          PrimitiveTypes::U8 |
+          PrimitiveTypes::I64 |
+          PrimitiveTypes::I32 |
+          PrimitiveTypes::I16 |
+          PrimitiveTypes::I8 => program.push(Operation::PushInt(symbols.clone())),
+          // integer literals can end up as f64, e.g. the '2' in 'x ** 2'
+          PrimitiveTypes::F64 if !symbols.contains('.') => program.push(Operation::PushFloat(format!("{symbols}.0"))),
           PrimitiveTypes::F64 => program.push(Operation::PushFloat(symbols.clone())),
           PrimitiveTypes::Bool => program.push(Operation::PushBool(symbols.clone())),
+          PrimitiveTypes::Str => {
+            program.strings.push(symbols.clone());
+            program.push(Operation::PushStr(program.strings.len() - 1));
+          }
 
           PrimitiveTypes::Number |
           PrimitiveTypes::Float |
           PrimitiveTypes::Integer |
           PrimitiveTypes::Void |
+          PrimitiveTypes::Array(_, _) |
+          PrimitiveTypes::Struct(_, _) |
           PrimitiveTypes::COUNT => {
-            self.panic_loc(node, format!("Found unsupported Primitve Type in translate_node: {:#?}, {symbols}", typ).as_str())
+            return Err(self.error(node, format!("Found unsupported Primitve Type in translate_node: {:#?}, {symbols}", typ)))
           }
         }
       }
       ASTNodeType::Identifier(ref name, _) => {
         let Some(var_type) = self.get_var(name) else {
-          self.panic_loc(node, format!("'{}' was not declared!", name).as_str())
+          return Err(self.error(node, format!("'{}' was not declared!", name)))
         };
         match var_type {
           VarriableType::Const(ConstVariable(name, value_type, _)) |
           VarriableType::Global(name, value_type) => {
             match value_type {
               PrimitiveTypes::Bool |
-              PrimitiveTypes::U64 => program.push(Operation::LoadInt(name)),
+              PrimitiveTypes::Str |
+              PrimitiveTypes::U64 |
+              PrimitiveTypes::U32 |
+              PrimitiveTypes::U16 |
+              PrimitiveTypes::U8 |
+              PrimitiveTypes::I64 |
+              PrimitiveTypes::I32 |
+              PrimitiveTypes::I16 |
+              PrimitiveTypes::I8 => program.push(Operation::LoadInt(name, value_type)),
               PrimitiveTypes::F64 => program.push(Operation::LoadFloat(name)),
 
               PrimitiveTypes::Number |
               PrimitiveTypes::Float |
               PrimitiveTypes::Integer |
               PrimitiveTypes::Void |
-              PrimitiveTypes::COUNT => self.panic_loc(node, "unexpected type"),
+              PrimitiveTypes::COUNT => return Err(self.error(node, "unexpected type")),
+              PrimitiveTypes::Array(_, _) => return Err(self.error(node, format!("The array '{}' can only be used through its elements", name))),
+              PrimitiveTypes::Struct(_, _) => return Err(self.error(node, format!("The instance '{}' can only be used through its fields", name))),
             }
           }
           VarriableType::Parameter(p) => {
             p.translate_load(program);
           }
+          VarriableType::Local(l) => {
+            l.translate_load(program);
+          }
         }
       }
-      ASTNodeType::BuiltinFunction(ref name, ref expr) => {
-        self.translate_node(expr, program);
+      ASTNodeType::BuiltinFunction(ref name, ref args) => {
         match name.as_str() {
-          "print_int" => program.push(Operation::PrintInt),
-          _ => self.panic_loc(node, format!("Unsupported builtin funcrion for translate_node: {}", name).as_str()),
+          "print_int" | "print_float" | "print_bool" | "print_str" | "print" => {},
+          _ => return Err(self.error(node, format!("Unsupported builtin funcrion for translate_node: {}", name))),
+        }
+        // like python, 'print' puts a space between the values
+        for (i, expr) in args.iter().enumerate() {
+          if i > 0 {
+            program.push(Operation::PrintChar(b' '));
+          }
+          match expr.node_type {
+            ASTNodeType::FString(ref parts) => self.translate_interpolations(parts, program)?,
+            _ => self.translate_print(expr, None, program)?,
+          }
+        }
+        program.push(Operation::PrintChar(b'\n'));
+      }
+      ASTNodeType::FString(_) => return Err(self.error(node, "f-strings can only be printed directly")),
+      ASTNodeType::Index(ref array, ref index, _) => {
+        let element = self.translate_element_address(array, index, program)?;
+        program.push(Operation::LoadElement(element));
+      }
+      ASTNodeType::IndexAssignment(ref array, ref index, ref value) => {
+        self.translate_node(value, program)?;
+        let element = self.translate_element_address(array, index, program)?;
+        program.push(Operation::StoreElement(element));
+      }
+      ASTNodeType::Len(ref value) => {
+        match value.node_type {
+          ASTNodeType::Identifier(_, PrimitiveTypes::Array(_, len)) => program.push(Operation::PushInt(len.to_string())),
+          _ => {
+            self.translate_node(value, program)?;
+            program.push(Operation::StrLen);
+          }
+        }
+      }
+      ASTNodeType::List(_, _) => return Err(self.error(node, "Lists can only initialize arrays")),
+      ASTNodeType::Field(_, _, _, _) => {
+        let field_type = self.translate_field_address(node, program)?;
+        program.push(Operation::LoadElement(field_type));
+      }
+      ASTNodeType::FieldAssignment(ref field, ref value) => {
+        self.translate_node(value, program)?;
+        let field_type = self.translate_field_address(field, program)?;
+        program.push(Operation::StoreElement(field_type));
+      }
+      // only the type checker needs the layout, the fields already know their offset
+      ASTNodeType::Class(_, _) => {}
+      ASTNodeType::Declaration(ref name, ref value_type, ref expr) if program.target != OperationsType::Main => {
+        let OperationsType::Function(function) = program.target.clone() else {
+          return Err(self.error(node, "Expected to be inside a function"))
+        };
+        let local = self.declare_local_var(node, name, &function)?;
+        match expr.as_deref() {
+          Some(ASTNode { node_type: ASTNodeType::List(ref elements, _), .. }) => self.translate_list(name, value_type, elements, program)?,
+          Some(expr) => {
+            self.translate_node(expr, program)?;
+            local.translate_store(program);
+          }
+          // zeroed like a global in '.bss', the slot still holds whatever the last call left there
+          None => local.translate_clear(program),
         }
       }
       ASTNodeType::Declaration(ref name, ref value_type, ref expr) => {
-        self.delcare_global_var(node, name, value_type.clone());
+        self.delcare_global_var(node, name, value_type.clone())?;
         self.vars.push(name.clone());
-        program.vars.push(name.clone());
-        match expr {
+        program.vars.push((name.clone(), value_type.slot_size()));
+        match expr.as_deref() {
           None => {}
-          Some(ref expr) => {
-            self.translate_node(expr, program);
-            program.push(Operation::StoreInt(name.clone()));
+          Some(ASTNode { node_type: ASTNodeType::List(ref elements, _), .. }) => self.translate_list(name, value_type, elements, program)?,
+          Some(expr) => {
+            self.translate_node(expr, program)?;
+            match value_type {
+              PrimitiveTypes::F64 => program.push(Operation::StoreFloat(name.clone())),
+              _ => program.push(Operation::StoreInt(name.clone(), value_type.clone())),
+            }
           }
         }
       }
       ASTNodeType::Const(_, _, _) => {
-        self.declare_const(node, program);
+        self.declare_const(node, program)?;
         // todo!()
       },
       ASTNodeType::If(ref cond, ref then, ref els) => {
-        self.translate_node(cond, program);
+        self.translate_node(cond, program)?;
         let n = self.get_ref_number();
         program.push(Operation::If(n));
-        self.translate_nodes(then, program);
+        self.translate_nodes(then, program)?;
         program.push(Operation::Else(n));
         if let Some(els) = els {
-          self.translate_nodes(els, program);
+          self.translate_nodes(els, program)?;
         }
         program.push(Operation::EndIF(n));
       }
@@ -368,33 +767,120 @@ impl  Builder {
       ASTNodeType::While(ref cond, ref body) => {
         let n = self.get_ref_number();
         program.push(Operation::While(n));
-        self.translate_node(cond, program);
+        self.translate_node(cond, program)?;
         program.push(Operation::CondWhile(n));
-        self.translate_nodes(body, program);
+        self.loops.push(n);
+        self.translate_nodes(body, program)?;
+        self.loops.pop();
+        program.push(Operation::StepWhile(n));
         program.push(Operation::EndWhile(n));
       }
+      ASTNodeType::For(ref name, ref var_type, ref start, ref stop, ref step, ref body) => {
+        // lowered to:
+        //   var i = start; var i.stop = stop; var i.step = step
+        //   while (i.step > 0 and i < i.stop) or (i.step < 0 and i > i.stop):
+        //     body
+        //     i = i + i.step
+        // The step has its own type and can be signed while i is not. The sum is done with 64
+        // bits and cut back to the type of i, so adding a negative step still counts down.
+        let (stop_name, step_name) = Builder::range_var_names(name);
+        let step_type = step.get_type().map_err(|msg| self.error(step, msg))?;
+        let loc = node.loc;
+        let expr = |node_type| Box::new(ASTNode { node_type, loc });
+        let var = |name: &String| expr(ASTNodeType::Identifier(name.clone(), var_type.clone()));
+        let step_var = || expr(ASTNodeType::Identifier(step_name.clone(), step_type.clone()));
+        let zero = || expr(ASTNodeType::Literal(step_type.clone(), "0".to_string()));
+        let bool_op = |left, op, right| expr(ASTNodeType::BinaryOp(left, op, right, PrimitiveTypes::Bool));
+
+        for (var_name, value_type, value) in [(name, var_type, start), (&stop_name, var_type, stop), (&step_name, &step_type, step)] {
+          let declaration = expr(ASTNodeType::Declaration(var_name.clone(), value_type.clone(), Some(value.clone())));
+          self.translate_node(&declaration, program)?;
+        }
+        let upwards = bool_op(bool_op(step_var(), Operator::Greater, zero()), Operator::And, bool_op(var(name), Operator::Less, var(&stop_name)));
+        let downwards = bool_op(bool_op(step_var(), Operator::Less, zero()), Operator::And, bool_op(var(name), Operator::Greater, var(&stop_name)));
+        let cond = bool_op(upwards, Operator::Or, downwards);
+        let increment = expr(ASTNodeType::Assignment(name.clone(), expr(ASTNodeType::BinaryOp(var(name), Operator::Plus, step_var(), var_type.clone()))));
+
+        let n = self.get_ref_number();
+        program.push(Operation::While(n));
+        self.translate_node(&cond, program)?;
+        program.push(Operation::CondWhile(n));
+        self.loops.push(n);
+        self.translate_nodes(body, program)?;
+        self.loops.pop();
+        program.push(Operation::StepWhile(n));
+        self.translate_node(&increment, program)?;
+        program.push(Operation::EndWhile(n));
+      }
+      ASTNodeType::ForEach(ref name, ref element_type, ref array, ref body) => {
+        // lowered to:
+        //   var x.index: u64 = 0
+        //   while x.index < len(a):
+        //     var x = a[x.index]
+        //     body
+        //     x.index = x.index + 1
+        let ASTNodeType::Identifier(_, PrimitiveTypes::Array(_, len)) = array.node_type else {
+          return Err(self.error(array, "Only arrays can be iterated over"))
+        };
+        let index_name = Builder::element_index_name(name);
+        let loc = node.loc;
+        let expr = |node_type| Box::new(ASTNode { node_type, loc });
+        let index = || expr(ASTNodeType::Identifier(index_name.clone(), PrimitiveTypes::U64));
+        let number = |n: usize| expr(ASTNodeType::Literal(PrimitiveTypes::U64, n.to_string()));
+
+        let declaration = expr(ASTNodeType::Declaration(index_name.clone(), PrimitiveTypes::U64, Some(number(0))));
+        self.translate_node(&declaration, program)?;
+        let cond = expr(ASTNodeType::BinaryOp(index(), Operator::Less, number(len), PrimitiveTypes::Bool));
+        let element = expr(ASTNodeType::Index(array.clone(), index(), element_type.clone()));
+        let element = expr(ASTNodeType::Declaration(name.clone(), element_type.clone(), Some(element)));
+        let increment = expr(ASTNodeType::Assignment(index_name.clone(), expr(ASTNodeType::BinaryOp(index(), Operator::Plus, number(1), PrimitiveTypes::U64))));
+
+        let n = self.get_ref_number();
+        program.push(Operation::While(n));
+        self.translate_node(&cond, program)?;
+        program.push(Operation::CondWhile(n));
+        self.translate_node(&element, program)?;
+        self.loops.push(n);
+        self.translate_nodes(body, program)?;
+        self.loops.pop();
+        program.push(Operation::StepWhile(n));
+        self.translate_node(&increment, program)?;
+        program.push(Operation::EndWhile(n));
+      }
+      ASTNodeType::Break => {
+        let Some(&n) = self.loops.last() else {
+          return Err(self.error(node, "'break' outside of a loop"))
+        };
+        program.push(Operation::Break(n));
+      }
+      ASTNodeType::Continue => {
+        let Some(&n) = self.loops.last() else {
+          return Err(self.error(node, "'continue' outside of a loop"))
+        };
+        program.push(Operation::Continue(n));
+      }
       ASTNodeType::SExpression(ref expr) => {
-        self.translate_node(expr, program);
+        self.translate_node(expr, program)?;
         program.push(Operation::PopStack);
       }
       ASTNodeType::FunctionCall(ref name, ref args, _) => {
         let Some(def_args) = self.functions.get(name) else {
-          self.panic_loc(node, format!("Tried to call function '{}' with '{}' arguments and was never defined!", name, args.len()).as_str())
+          return Err(self.error(node, format!("Tried to call function '{}' with '{}' arguments and was never defined!", name, args.len())))
         };
         let def_args = def_args.clone();
         if args.len() != def_args.len() {
-          self.panic_loc(node, "Not the right amount of parameters")
+          return Err(self.error(node, "Not the right amount of parameters"))
         }
-        for (i, expr) in args.iter().enumerate() {
-          self.translate_node(expr, program);
-          def_args.trnslate_caller_argument(i, program);
+        for expr in args {
+          self.translate_node(expr, program)?;
         }
-        program.push(Operation::FunctionCall(name.clone(), 0));
+        let arguments_size = def_args.translate_caller_arguments(program);
+        program.push(Operation::FunctionCall(name.clone(), arguments_size));
         def_args.translate_function_call(program);
       }
       ASTNodeType::FunctionDef( ref name, ref args, _, ref body ) => {
         if program.target != OperationsType::Main {
-          self.panic_loc(node, "Can not define function inside a function")
+          return Err(self.error(node, "Can not define function inside a function"))
         }
 
         let func_name = Rc::new(name.clone());
@@ -402,7 +888,7 @@ impl  Builder {
         self.scopes.push(Scope {name: HashMap::new()}); // scope for parameters
         program.push(Operation::BeginFunction(name.clone()));
         let Some(parameters) = self.functions.get(name) else {
-          self.panic_loc(node, &format!("Could not find function '{}' while building the program", name))
+          return Err(self.error(node, format!("Could not find function '{}' while building the program", name)))
         };
         if let Some(args) = args {
           let scope = self.scopes.last_mut().unwrap();
@@ -412,26 +898,32 @@ impl  Builder {
             let arg_name = Rc::new(arg_name.clone());
             scope.insert(arg_name, VarriableType::Parameter(p.clone()));
           }
-          program.push(Operation::ReserveParameters(parameters.reserved_stack()));
         };
-        self.translate_nodes(body, program);
+        if parameters.reserved_stack() > 0 {
+          program.push(Operation::ReserveParameters(parameters.reserved_stack()));
+        }
+        self.translate_nodes(body, program)?;
         program.push(Operation::EndFunction(func_name.deref().clone()));
         program.target = OperationsType::Main;
         self.scopes.pop(); // remove parameters
       }
       ASTNodeType::Return(ref expr) => {
         if let Some(ref expr) = expr {
-          self.translate_node(expr, program);
+          self.translate_node(expr, program)?;
         }
         let OperationsType::Function(ref name) = program.target.clone() else {
-          panic!()
+          return Err(self.error(node, "Can not return outside of a function"))
         };
         let Some(function) = self.functions.get(name) else {
-          panic!()
+          return Err(self.error(node, format!("Could not find function '{}' while building the program", name)))
         };
         function.translate_return(program);
         program.push(Operation::Return(name.deref().clone()));
       }
+      ASTNodeType::Error => {
+        return Err(self.error(node, "Can not build a statement that failed to parse"))
+      }
     }
+    Ok(())
   }
 }
@@ -1,6 +1,17 @@
 use crate::{ast::{ConstLiteral, PrimitiveTypes}, operations::{ConstVariable, Operation, Program}};
 
 const INTEGER_ARGUMENT_ORDDER: [&str; 6] = ["rdi", "rsi", "rdx", "rcx", "r8", "r9"];
+// the same registers by width: 64, 32, 16 and 8 bit
+const INTEGER_ARGUMENT_PARTS: [[&str; 4]; 6] = [
+  ["rdi", "edi", "di", "dil"],
+  ["rsi", "esi", "si", "sil"],
+  ["rdx", "edx", "dx", "dl"],
+  ["rcx", "ecx", "cx", "cl"],
+  ["r8", "r8d", "r8w", "r8b"],
+  ["r9", "r9d", "r9w", "r9b"],
+];
+const RAX_PARTS: [&str; 4] = ["rax", "eax", "ax", "al"];
+const MEMORY_SIZES: [&str; 4] = ["QWORD", "DWORD", "WORD", "BYTE"];
 const SSE_ARRGUMENT_ORDER: [&str; 8] = ["xmm0", "xmm1", "xmm2", "xmm3", "xmm4", "xmm5", "xmm6", "xmm7"];
 const _INTEGER_RETURN_ORDER: [&str; 2] = ["rax", "rdx"];
 
@@ -47,12 +58,29 @@ print_int:
     ret
 ";
 
+// prints a '-' for negative numbers and the rest through 'print_int'
+const PRINT_SIGNED_INT_ASM: &str = "
+print_signed_int:
+    test    rdi, rdi
+    jns     print_int
+    push    rdi
+    push    45
+    mov     rax, 1
+    mov     rdi, 1
+    mov     rsi, rsp
+    mov     rdx, 1
+    syscall
+    pop     rax
+    pop     rdi
+    neg     rdi
+    jmp     print_int
+";
+
 const PRINT_INT_ASM: &str ="
 print_int:
     mov     r9, -3689348814741910323
     sub     rsp, 40
-    mov     BYTE [rsp+31], 10
-    lea     rcx, [rsp+30]
+    lea     rcx, [rsp+31]
 .L2:
     mov     rax, rdi
     lea     r8, [rsp+32]
@@ -83,6 +111,479 @@ print_int:
     ret
 ";
 
+// Prints 'true' and 'false' the python way: 'True' and 'False'
+const PRINT_BOOL_ASM: &str = "
+print_bool:
+    test    rdi, rdi
+    jz      .print_bool_false
+    mov     rax, 0x65757254
+    mov     rdx, 4
+    jmp     .print_bool_write
+.print_bool_false:
+    mov     rax, 0x65736c6146
+    mov     rdx, 5
+.print_bool_write:
+    push    rax
+    mov     rax, 1
+    mov     rdi, 1
+    mov     rsi, rsp
+    syscall
+    pop     rax
+    ret
+";
+
+// Prints the f64 in rdi with rsi decimals. If rdx is not 0, trailing zeros are dropped but one
+// decimal stays: '2.5', '-0.125', '3.0'. Without decimals there is no '.' either.
+// Values from 1e16 on are written as 'd.ddddddde+NN', like python does. nan and inf are spelled out.
+// The text is built backwards from [rsp+64], rcx points at its first char.
+const PRINT_FLOAT_ASM: &str = "
+print_float:
+    sub     rsp, 72
+    mov     [rsp], rsi
+    mov     [rsp+8], rdx
+    lea     rcx, [rsp+64]
+    mov     r10, rdi
+    movq    xmm0, rdi
+    ucomisd xmm0, xmm0
+    jp      .print_float_nan
+    btr     rdi, 63
+    movq    xmm0, rdi
+    mov     rax, 0x7ff0000000000000
+    cmp     rdi, rax
+    je      .print_float_inf
+    xor     r11, r11
+    mov     rax, 0x4341c37937e08000
+    movq    xmm1, rax
+    ucomisd xmm0, xmm1
+    jb      .print_float_split
+    mov     rax, 0x4024000000000000
+    movq    xmm1, rax
+.print_float_scale:
+    divsd   xmm0, xmm1
+    inc     r11
+    ucomisd xmm0, xmm1
+    jae     .print_float_scale
+.print_float_split:
+    cvttsd2si r8, xmm0
+    cvtsi2sd xmm1, r8
+    subsd   xmm0, xmm1
+    mov     rax, 1
+    mov     rsi, [rsp]
+.print_float_multiplier:
+    test    rsi, rsi
+    jz      .print_float_round
+    imul    rax, rax, 10
+    dec     rsi
+    jmp     .print_float_multiplier
+.print_float_round:
+    cvtsi2sd xmm1, rax
+    mulsd   xmm0, xmm1
+    roundsd xmm0, xmm0, 0
+    cvttsd2si r9, xmm0
+    cmp     r9, rax
+    jb      .print_float_exponent
+    sub     r9, rax
+    inc     r8
+.print_float_exponent:
+    mov     rsi, 10
+    test    r11, r11
+    jz      .print_float_fraction
+    mov     rax, r11
+.print_float_exponent_digit:
+    xor     edx, edx
+    div     rsi
+    add     dl, 48
+    dec     rcx
+    mov     BYTE [rcx], dl
+    test    rax, rax
+    jnz     .print_float_exponent_digit
+    cmp     r11, 10
+    jae     .print_float_exponent_sign
+    dec     rcx
+    mov     BYTE [rcx], 48
+.print_float_exponent_sign:
+    dec     rcx
+    mov     BYTE [rcx], 43
+    dec     rcx
+    mov     BYTE [rcx], 101
+.print_float_fraction:
+    mov     rdi, [rsp]
+    test    rdi, rdi
+    jz      .print_float_integer
+    mov     rax, r9
+    xor     r11, r11
+.print_float_fraction_digit:
+    xor     edx, edx
+    div     rsi
+    dec     rdi
+    test    r11, r11
+    jnz     .print_float_fraction_write
+    test    rdx, rdx
+    jnz     .print_float_fraction_write
+    cmp     QWORD [rsp+8], 0
+    je      .print_float_fraction_write
+    test    rdi, rdi
+    jnz     .print_float_fraction_next
+.print_float_fraction_write:
+    add     dl, 48
+    dec     rcx
+    mov     BYTE [rcx], dl
+    inc     r11
+.print_float_fraction_next:
+    test    rdi, rdi
+    jnz     .print_float_fraction_digit
+    dec     rcx
+    mov     BYTE [rcx], 46
+.print_float_integer:
+    mov     rax, r8
+.print_float_integer_digit:
+    xor     edx, edx
+    div     rsi
+    add     dl, 48
+    dec     rcx
+    mov     BYTE [rcx], dl
+    test    rax, rax
+    jnz     .print_float_integer_digit
+.print_float_sign:
+    test    r10, r10
+    jns     .print_float_write
+    dec     rcx
+    mov     BYTE [rcx], 45
+    jmp     .print_float_write
+.print_float_inf:
+    mov     BYTE [rsp+61], 105
+    mov     BYTE [rsp+62], 110
+    mov     BYTE [rsp+63], 102
+    lea     rcx, [rsp+61]
+    jmp     .print_float_sign
+.print_float_nan:
+    mov     BYTE [rsp+61], 110
+    mov     BYTE [rsp+62], 97
+    mov     BYTE [rsp+63], 110
+    lea     rcx, [rsp+61]
+.print_float_write:
+    lea     rdx, [rsp+64]
+    sub     rdx, rcx
+    mov     rsi, rcx
+    mov     rax, 1
+    mov     rdi, 1
+    syscall
+    add     rsp, 72
+    ret
+";
+
+// Prints the f64 in rdi with rsi significant digits like python's '{x:.N}': '3.14', '0.00012',
+// '3.0', but '1.234e+03' once the exponent reaches N - 1 and '1.2e-05' below -4. Trailing zeros
+// are dropped, in plain notation one decimal stays. Zero, nan and inf are left to print_float.
+const PRINT_FLOAT_GENERAL_ASM: &str = "
+print_float_general:
+    test    rsi, rsi
+    jnz     .print_float_general_check
+    mov     rsi, 1
+.print_float_general_check:
+    mov     rax, rdi
+    btr     rax, 63
+    mov     rdx, 0x7ff0000000000000
+    cmp     rax, rdx
+    jae     .print_float_general_special
+    test    rax, rax
+    jz      .print_float_general_special
+    sub     rsp, 72
+    mov     r10, rdi
+    mov     [rsp+16], rax
+    lea     rcx, [rsp+64]
+    movq    xmm0, rax
+    xor     r11, r11
+    mov     rax, 0x4024000000000000
+    movq    xmm1, rax
+    mov     rax, 0x3ff0000000000000
+    movq    xmm2, rax
+.print_float_general_down:
+    ucomisd xmm0, xmm1
+    jb      .print_float_general_up
+    divsd   xmm0, xmm1
+    inc     r11
+    jmp     .print_float_general_down
+.print_float_general_up:
+    ucomisd xmm0, xmm2
+    jae     .print_float_general_digits
+    mulsd   xmm0, xmm1
+    dec     r11
+    jmp     .print_float_general_up
+.print_float_general_digits:
+    mov     r8, 1
+    mov     rdi, rsi
+.print_float_general_multiplier:
+    dec     rdi
+    jz      .print_float_general_scale
+    imul    r8, r8, 10
+    jmp     .print_float_general_multiplier
+.print_float_general_scale:
+    lea     rdi, [rsi-1]
+    sub     rdi, r11
+    mov     rax, rdi
+    test    rax, rax
+    jns     .print_float_general_scale_big
+    neg     rax
+.print_float_general_scale_big:
+    mov     rdx, [rsp+16]
+    movq    xmm2, rdx
+    mov     rdx, 0x4480f0cf064dd592
+    movq    xmm1, rdx
+.print_float_general_scale_big_next:
+    cmp     rax, 22
+    jbe     .print_float_general_power
+    sub     rax, 22
+    test    rdi, rdi
+    js      .print_float_general_scale_big_down
+    mulsd   xmm2, xmm1
+    jmp     .print_float_general_scale_big_next
+.print_float_general_scale_big_down:
+    divsd   xmm2, xmm1
+    jmp     .print_float_general_scale_big_next
+.print_float_general_power:
+    mov     rdx, 0x4024000000000000
+    movq    xmm1, rdx
+    mov     rdx, 0x3ff0000000000000
+    movq    xmm0, rdx
+.print_float_general_power_next:
+    test    rax, rax
+    jz      .print_float_general_round
+    mulsd   xmm0, xmm1
+    dec     rax
+    jmp     .print_float_general_power_next
+.print_float_general_round:
+    test    rdi, rdi
+    js      .print_float_general_round_down
+    mulsd   xmm2, xmm0
+    jmp     .print_float_general_round_digits
+.print_float_general_round_down:
+    divsd   xmm2, xmm0
+.print_float_general_round_digits:
+    roundsd xmm2, xmm2, 0
+    cvttsd2si r9, xmm2
+    imul    rax, r8, 10
+    cmp     r9, rax
+    jb      .print_float_general_low
+    inc     r11
+    jmp     .print_float_general_scale
+.print_float_general_low:
+    cmp     r9, r8
+    jae     .print_float_general_notation
+    dec     r11
+    jmp     .print_float_general_scale
+.print_float_general_notation:
+    lea     rdi, [rsi-1]
+    xor     r8, r8
+    cmp     r11, -4
+    jl      .print_float_general_exponent
+    cmp     r11, rdi
+    jge     .print_float_general_exponent
+    sub     rdi, r11
+    mov     r8, 1
+    jmp     .print_float_general_trim
+.print_float_general_exponent:
+    mov     rsi, 10
+    mov     BYTE [rsp+8], 43
+    mov     rax, r11
+    test    rax, rax
+    jns     .print_float_general_exponent_abs
+    mov     BYTE [rsp+8], 45
+    neg     rax
+.print_float_general_exponent_abs:
+    mov     r11, rax
+.print_float_general_exponent_digit:
+    xor     edx, edx
+    div     rsi
+    add     dl, 48
+    dec     rcx
+    mov     BYTE [rcx], dl
+    test    rax, rax
+    jnz     .print_float_general_exponent_digit
+    cmp     r11, 10
+    jae     .print_float_general_exponent_sign
+    dec     rcx
+    mov     BYTE [rcx], 48
+.print_float_general_exponent_sign:
+    mov     dl, BYTE [rsp+8]
+    dec     rcx
+    mov     BYTE [rcx], dl
+    dec     rcx
+    mov     BYTE [rcx], 101
+.print_float_general_trim:
+    mov     rsi, 10
+    cmp     rdi, r8
+    jbe     .print_float_general_fraction
+    mov     rax, r9
+    xor     edx, edx
+    div     rsi
+    test    rdx, rdx
+    jnz     .print_float_general_fraction
+    mov     r9, rax
+    dec     rdi
+    jmp     .print_float_general_trim
+.print_float_general_fraction:
+    test    rdi, rdi
+    jz      .print_float_general_integer
+    mov     rax, r9
+.print_float_general_fraction_digit:
+    xor     edx, edx
+    div     rsi
+    add     dl, 48
+    dec     rcx
+    mov     BYTE [rcx], dl
+    dec     rdi
+    jnz     .print_float_general_fraction_digit
+    mov     r9, rax
+    dec     rcx
+    mov     BYTE [rcx], 46
+.print_float_general_integer:
+    mov     rax, r9
+.print_float_general_integer_digit:
+    xor     edx, edx
+    div     rsi
+    add     dl, 48
+    dec     rcx
+    mov     BYTE [rcx], dl
+    test    rax, rax
+    jnz     .print_float_general_integer_digit
+    test    r10, r10
+    jns     .print_float_general_write
+    dec     rcx
+    mov     BYTE [rcx], 45
+.print_float_general_write:
+    lea     rdx, [rsp+64]
+    sub     rdx, rcx
+    mov     rsi, rcx
+    mov     rax, 1
+    mov     rdi, 1
+    syscall
+    add     rsp, 72
+    ret
+.print_float_general_special:
+    mov     rsi, 1
+    mov     rdx, 1
+    jmp     print_float
+";
+
+const INDEX_ERROR_MESSAGE: &str = "IndexError: array index out of range\n";
+
+// Jumped to by a failed bounds check. Writes the message to stderr and exits with status 1.
+const INDEX_ERROR_ASM: &str = "
+index_error:
+    lea     rsi, [INDEX_ERROR_MESSAGE]
+    mov     rdx, [rsi]
+    add     rsi, 8
+    mov     rax, 1
+    mov     rdi, 2
+    syscall
+    mov     rax, 60
+    mov     rdi, 1
+    syscall
+";
+
+// base ** exponent by squaring. Both are passed on the stack, the result replaces the base.
+// Negative exponents truncate like integer division: 0, unless the base is 1 or -1.
+const POWER_INT_ASM: &str = "
+pow_int:
+    mov     rax, 1
+    mov     rbx, [rsp+16]
+    mov     r12, [rsp+8]
+.pow_int_loop:
+    test    r12, r12
+    jz      .pow_int_done
+    test    r12, 1
+    jz      .pow_int_square
+    imul    rax, rbx
+.pow_int_square:
+    imul    rbx, rbx
+    shr     r12, 1
+    jmp     .pow_int_loop
+.pow_int_done:
+    mov     [rsp+16], rax
+    ret
+
+pow_signed_int:
+    cmp     QWORD [rsp+8], 0
+    jge     pow_int
+    mov     rax, [rsp+16]
+    cmp     rax, 1
+    je      .pow_signed_int_done
+    cmp     rax, -1
+    jne     .pow_signed_int_zero
+    test    QWORD [rsp+8], 1
+    jnz     .pow_signed_int_done
+    mov     rax, 1
+    jmp     .pow_signed_int_done
+.pow_signed_int_zero:
+    xor     rax, rax
+.pow_signed_int_done:
+    mov     [rsp+16], rax
+    ret
+";
+
+// base ** exponent for f64 as 2 ** (exponent * log2(|base|)) on the x87 fpu. Passed like 'pow_int'.
+// Negative bases need an integral exponent (NaN otherwise), odd ones flip the sign.
+// 0 ** y is 1 for y == 0, 0 for y > 0 and inf for y < 0.
+const POWER_FLOAT_ASM: &str = "
+pow_float:
+    fld     QWORD [rsp+8]
+    fld     QWORD [rsp+16]
+    fldz
+    fcomip  st0, st1
+    jp      .pow_float_nan
+    je      .pow_float_zero_base
+    fld     st0
+    fabs
+    fld     st2
+    fxch
+    fyl2x
+    fld     st0
+    frndint
+    fsub    st1, st0
+    fxch
+    f2xm1
+    fld1
+    faddp
+    fscale
+    fstp    st1
+    fldz
+    fcomip  st0, st2
+    jb      .pow_float_done
+    fld     st2
+    fld     st0
+    frndint
+    fcomip  st0, st1
+    fstp    st0
+    jne     .pow_float_nan
+    fld     st2
+    fistp   QWORD [rsp+8]
+    test    QWORD [rsp+8], 1
+    jz      .pow_float_done
+    fchs
+.pow_float_done:
+    fstp    QWORD [rsp+16]
+    fninit
+    ret
+.pow_float_nan:
+    fninit
+    mov     rax, 0x7ff8000000000000
+    mov     [rsp+16], rax
+    ret
+.pow_float_zero_base:
+    fld     st1
+    fldz
+    fcomip  st0, st1
+    fninit
+    mov     rax, 0x3ff0000000000000
+    je      .pow_float_zero_base_done
+    mov     rax, 0
+    jb      .pow_float_zero_base_done
+    mov     rax, 0x7ff0000000000000
+.pow_float_zero_base_done:
+    mov     [rsp+16], rax
+    ret
+";
 
 pub struct Compiler {
 }
@@ -94,6 +595,13 @@ impl Compiler {
     // executable part
     output.push_str("segment .text\n");
     output.push_str(PRINT_INT_ASM);
+    output.push_str(PRINT_SIGNED_INT_ASM);
+    output.push_str(PRINT_FLOAT_ASM);
+    output.push_str(PRINT_FLOAT_GENERAL_ASM);
+    output.push_str(PRINT_BOOL_ASM);
+    output.push_str(INDEX_ERROR_ASM);
+    output.push_str(POWER_INT_ASM);
+    output.push_str(POWER_FLOAT_ASM);
     // defined functions
     let functions = Compiler::translate_operations(&program.function_defs);
     output.push_str(&functions);
@@ -109,13 +617,20 @@ impl Compiler {
     output.push('\n');
     // uninitialized data
     output.push_str("segment .bss\n");
-    for name in program.vars {
-      output.push_str(format!("{}: resb 8\n", name).as_str());
+    for (name, size) in program.vars {
+      output.push_str(format!("{}: resb {}\n", name, size).as_str());
     }
     output.push_str("segment .data\n");
     for const_ @ ConstVariable(name, const_type, value) in &program.consts {
       match const_type {
-        PrimitiveTypes::U64 => {
+        PrimitiveTypes::U64 |
+        PrimitiveTypes::U32 |
+        PrimitiveTypes::U16 |
+        PrimitiveTypes::U8 |
+        PrimitiveTypes::I64 |
+        PrimitiveTypes::I32 |
+        PrimitiveTypes::I16 |
+        PrimitiveTypes::I8 => {
           let ConstLiteral::Integer(value) = value else {
             panic!("Error during compilation. Mismatch of const while building data segment {:?}", const_)
           };
@@ -134,29 +649,120 @@ impl Compiler {
           let value = match value.as_str() {
             "true" => "1",
             "false" => "0",
-            _ => panic!()
+            _ => unreachable!("the lexer only produces 'true' and 'false' as bool literals"),
           };
           output.push_str(&format!("    {name}: dq {value}\n"));
         },
 
+        PrimitiveTypes::Str |
         PrimitiveTypes::Number |
         PrimitiveTypes::Float |
         PrimitiveTypes::Integer |
         PrimitiveTypes::Void |
-        PrimitiveTypes::COUNT => panic!(),
+        PrimitiveTypes::Array(_, _) |
+        PrimitiveTypes::Struct(_, _) |
+        PrimitiveTypes::COUNT => unreachable!("the type checker rejects consts of type '{}'", const_type),
       }
     }
+    // string literals: the length followed by the utf-8 bytes
+    for (i, value) in program.strings.iter().enumerate() {
+      Compiler::string_data(&mut output, &format!("STR_{i}"), value);
+    }
+    Compiler::string_data(&mut output, "INDEX_ERROR_MESSAGE", INDEX_ERROR_MESSAGE);
     output.push_str("    true dq 0x0000000000000001\n");
     output.push_str("    false dq 0x0000000000000000\n");
     output
   }
 
+  fn string_data(output: &mut String, label: &str, value: &str) {
+    output.push_str(&format!("    {label}: dq {}\n", value.len()));
+    if !value.is_empty() {
+      let bytes: Vec<String> = value.bytes().map(|byte| byte.to_string()).collect();
+      output.push_str(&format!("    db {}\n", bytes.join(", ")));
+    }
+  }
+
+  // index into the '_PARTS' and 'MEMORY_SIZES' tables
+  fn width_index(typ: &PrimitiveTypes) -> usize {
+    match typ.size() {
+      1 => 3,
+      2 => 2,
+      4 => 1,
+      _ => 0,
+    }
+  }
+
+  // Integers only take the bytes of their type in memory, but are sign or zero extended to
+  // 64 bit once they are loaded onto the stack.
+  fn store_int(output: &mut String, address: &str, typ: &PrimitiveTypes) {
+    let i = Compiler::width_index(typ);
+    output.push_str("    pop rax\n");
+    output.push_str(format!("    mov {} [{}], {}\n", MEMORY_SIZES[i], address, RAX_PARTS[i]).as_str());
+  }
+
+  fn load_int(output: &mut String, address: &str, typ: &PrimitiveTypes) {
+    let i = Compiler::width_index(typ);
+    let load = match (i, typ.is_signed()) {
+      (0, _) => format!("mov rax, QWORD [{address}]"),
+      (1, false) => format!("mov eax, DWORD [{address}]"),
+      (1, true) => format!("movsxd rax, DWORD [{address}]"),
+      (_, false) => format!("movzx rax, {} [{address}]", MEMORY_SIZES[i]),
+      (_, true) => format!("movsx rax, {} [{address}]", MEMORY_SIZES[i]),
+    };
+    output.push_str(format!("    {load}\n").as_str());
+    output.push_str("    push rax\n");
+  }
+
+  // Shifts by a count in 'cl'. 'rcx' may already hold an argument of a call, so it is restored.
+  fn shift_int(output: &mut String, shift: &str) {
+    output.push_str("    mov r12, rcx\n");
+    output.push_str("    pop rcx\n");
+    output.push_str("    pop rax\n");
+    output.push_str(format!("    {shift} rax, cl\n").as_str());
+    output.push_str("    mov rcx, r12\n");
+    output.push_str("    push rax\n");
+  }
+
+  // Pops two integers and pushes whether the 'cmov' condition holds for them.
+  fn compare_int(output: &mut String, cmov: &str) {
+    output.push_str("    mov r12, [false]\n");
+    output.push_str("    mov r13, [true]\n");
+    output.push_str("    pop rbx\n");
+    output.push_str("    pop rax\n");
+    output.push_str("    cmp rax, rbx\n");
+    output.push_str(format!("    {cmov} r12, r13\n").as_str());
+    output.push_str("    push r12\n");
+  }
+
+  // Same for floats. 'ucomisd' sets the flags like an unsigned compare, so only the 'above'
+  // conditions are used and 'less' swaps the operands. If one of them is NaN the parity flag
+  // is set and the result is 'unordered' instead.
+  fn compare_float(output: &mut String, cmov: &str, swap: bool, unordered: &str) {
+    output.push_str("    mov r12, [false]\n");
+    output.push_str("    mov r13, [true]\n");
+    output.push_str("    pop rax\n");
+    output.push_str("    movq xmm7, rax\n");
+    output.push_str("    pop rax\n");
+    output.push_str("    movq xmm6, rax\n");
+    if swap {
+      output.push_str("    ucomisd xmm7, xmm6\n");
+    } else {
+      output.push_str("    ucomisd xmm6, xmm7\n");
+    }
+    output.push_str(format!("    {cmov} r12, r13\n").as_str());
+    output.push_str(format!("    mov r13, [{unordered}]\n").as_str());
+    output.push_str("    cmovp r12, r13\n");
+    output.push_str("    push r12\n");
+  }
+
   fn translate_operations(operations: &Vec<Operation>) -> String {
     let mut output: String = String::new();
     for step in operations {
       match step {
         Operation::PushInt(s) => {
-          output.push_str(&format!("    push {s}\n"));
+          // 'push' only takes 32 bit immediates
+          output.push_str(&format!("    mov rax, {s}\n"));
+          output.push_str("    push rax\n");
         },
         Operation::AddInt => {
           output.push_str("    pop rbx\n");
@@ -176,39 +782,130 @@ impl Compiler {
           output.push_str("    sub rax, rbx\n");
           output.push_str("    push rax\n");
         },
+        Operation::MultSignedInt => {
+          output.push_str("    pop rbx\n");
+          output.push_str("    pop rax\n");
+          output.push_str("    imul rax, rbx\n");
+          output.push_str("    push rax\n");
+        },
         Operation::DivInt => {
           output.push_str("    pop rbx\n");
           output.push_str("    pop rax\n");
+          output.push_str("    xor rdx, rdx\n"); // div divides rdx:rax
           output.push_str("    div rbx\n");
           output.push_str("    push rax\n");
         },
-        Operation::EqualInt => {
-          output.push_str("    mov r12, [false]\n");
-          output.push_str("    mov r13, [true]\n");
+        Operation::DivSignedInt => {
           output.push_str("    pop rbx\n");
           output.push_str("    pop rax\n");
-          output.push_str("    cmp rax, rbx\n");
-          output.push_str("    cmove r12, r13\n");
-          output.push_str("    push r12\n");
-        }
-        Operation::GreaterInt => {
-          output.push_str("    mov r12, [false]\n");
-          output.push_str("    mov r13, [true]\n");
+          output.push_str("    cqo\n"); // sign extends rax into rdx
+          output.push_str("    idiv rbx\n");
+          output.push_str("    push rax\n");
+        },
+        Operation::FloorDivSignedInt => {
           output.push_str("    pop rbx\n");
           output.push_str("    pop rax\n");
-          output.push_str("    cmp rax, rbx\n");
-          output.push_str("    cmovg r12, r13\n");
-          output.push_str("    push r12\n");
-        }
-        Operation::LessInt => {
-          output.push_str("    mov r12, [false]\n");
-          output.push_str("    mov r13, [true]\n");
+          output.push_str("    cqo\n");
+          output.push_str("    idiv rbx\n");
+          // idiv truncates, so step one down when there is a remainder and the signs differ
+          output.push_str("    mov r12, rdx\n");
+          output.push_str("    xor r12, rbx\n");
+          output.push_str("    lea r13, [rax - 1]\n");
+          output.push_str("    test rdx, rdx\n");
+          output.push_str("    cmovz r13, rax\n");
+          output.push_str("    test r12, r12\n");
+          output.push_str("    cmovs rax, r13\n");
+          output.push_str("    push rax\n");
+        },
+        Operation::ModInt => {
+          output.push_str("    pop rbx\n");
+          output.push_str("    pop rax\n");
+          output.push_str("    xor rdx, rdx\n");
+          output.push_str("    div rbx\n");
+          output.push_str("    push rdx\n");
+        },
+        Operation::ModSignedInt => {
+          output.push_str("    pop rbx\n");
+          output.push_str("    pop rax\n");
+          output.push_str("    cqo\n");
+          output.push_str("    idiv rbx\n");
+          // the remainder takes the sign of the divisor
+          output.push_str("    mov r12, rdx\n");
+          output.push_str("    xor r12, rbx\n");
+          output.push_str("    lea r13, [rdx + rbx]\n");
+          output.push_str("    test rdx, rdx\n");
+          output.push_str("    cmovz r13, rdx\n");
+          output.push_str("    test r12, r12\n");
+          output.push_str("    cmovs rdx, r13\n");
+          output.push_str("    push rdx\n");
+        },
+        Operation::PowerInt => {
+          output.push_str("    call pow_int\n");
+          output.push_str("    add rsp, 8\n");
+        },
+        Operation::PowerSignedInt => {
+          output.push_str("    call pow_signed_int\n");
+          output.push_str("    add rsp, 8\n");
+        },
+        Operation::BitAndInt => {
           output.push_str("    pop rbx\n");
           output.push_str("    pop rax\n");
-          output.push_str("    cmp rax, rbx\n");
-          output.push_str("    cmovl r12, r13\n");
-          output.push_str("    push r12\n");
+          output.push_str("    and rax, rbx\n");
+          output.push_str("    push rax\n");
+        },
+        Operation::BitOrInt => {
+          output.push_str("    pop rbx\n");
+          output.push_str("    pop rax\n");
+          output.push_str("    or rax, rbx\n");
+          output.push_str("    push rax\n");
+        },
+        Operation::BitXorInt => {
+          output.push_str("    pop rbx\n");
+          output.push_str("    pop rax\n");
+          output.push_str("    xor rax, rbx\n");
+          output.push_str("    push rax\n");
+        },
+        Operation::ShiftLeftInt => Compiler::shift_int(&mut output, "shl"),
+        Operation::ShiftRightInt => Compiler::shift_int(&mut output, "shr"),
+        Operation::ShiftRightSignedInt => Compiler::shift_int(&mut output, "sar"),
+        Operation::NegInt => {
+          output.push_str("    pop rax\n");
+          output.push_str("    neg rax\n");
+          output.push_str("    push rax\n");
         }
+        Operation::Truncate(typ) => {
+          let i = Compiler::width_index(typ);
+          output.push_str("    pop rax\n");
+          match (i, typ.is_signed()) {
+            (0, _) => {}
+            (1, false) => output.push_str("    mov eax, eax\n"),
+            (1, true) => output.push_str("    movsxd rax, eax\n"),
+            (_, false) => output.push_str(format!("    movzx rax, {}\n", RAX_PARTS[i]).as_str()),
+            (_, true) => output.push_str(format!("    movsx rax, {}\n", RAX_PARTS[i]).as_str()),
+          }
+          output.push_str("    push rax\n");
+        }
+        Operation::BitNotInt => {
+          output.push_str("    pop rax\n");
+          output.push_str("    not rax\n");
+          output.push_str("    push rax\n");
+        }
+        Operation::EqualInt => Compiler::compare_int(&mut output, "cmove"),
+        Operation::NotEqualInt => Compiler::compare_int(&mut output, "cmovne"),
+        Operation::GreaterInt => Compiler::compare_int(&mut output, "cmovg"),
+        Operation::GreaterEqualInt => Compiler::compare_int(&mut output, "cmovge"),
+        Operation::LessInt => Compiler::compare_int(&mut output, "cmovl"),
+        Operation::LessEqualInt => Compiler::compare_int(&mut output, "cmovle"),
+        Operation::AboveInt => Compiler::compare_int(&mut output, "cmova"),
+        Operation::AboveEqualInt => Compiler::compare_int(&mut output, "cmovae"),
+        Operation::BelowInt => Compiler::compare_int(&mut output, "cmovb"),
+        Operation::BelowEqualInt => Compiler::compare_int(&mut output, "cmovbe"),
+        Operation::EqualFloat => Compiler::compare_float(&mut output, "cmove", false, "false"),
+        Operation::NotEqualFloat => Compiler::compare_float(&mut output, "cmovne", false, "true"),
+        Operation::GreaterFloat => Compiler::compare_float(&mut output, "cmova", false, "false"),
+        Operation::GreaterEqualFloat => Compiler::compare_float(&mut output, "cmovae", false, "false"),
+        Operation::LessFloat => Compiler::compare_float(&mut output, "cmova", true, "false"),
+        Operation::LessEqualFloat => Compiler::compare_float(&mut output, "cmovae", true, "false"),
         Operation::PushFloat(s) => {
           output.push_str(format!("    mov rax, __?float64?__({s})\n").as_str());
           output.push_str("    push rax\n");
@@ -249,34 +946,215 @@ impl Compiler {
           output.push_str("    movq rax, xmm6\n");
           output.push_str("    push rax\n");
         }
+        Operation::FloorDivFloat => {
+          output.push_str("    pop rax\n");
+          output.push_str("    movq xmm7, rax\n");
+          output.push_str("    pop rax\n");
+          output.push_str("    movq xmm6, rax\n");
+          output.push_str("    divsd xmm6, xmm7\n");
+          output.push_str("    roundsd xmm6, xmm6, 1\n"); // round down
+          output.push_str("    movq rax, xmm6\n");
+          output.push_str("    push rax\n");
+        }
+        Operation::ModFloat => {
+          // a - floor(a / b) * b
+          output.push_str("    pop rax\n");
+          output.push_str("    movq xmm7, rax\n");
+          output.push_str("    pop rax\n");
+          output.push_str("    movq xmm6, rax\n");
+          output.push_str("    movq xmm8, rax\n");
+          output.push_str("    divsd xmm8, xmm7\n");
+          output.push_str("    roundsd xmm8, xmm8, 1\n");
+          output.push_str("    mulsd xmm8, xmm7\n");
+          output.push_str("    subsd xmm6, xmm8\n");
+          output.push_str("    movq rax, xmm6\n");
+          output.push_str("    push rax\n");
+        }
+        Operation::PowerFloat => {
+          output.push_str("    call pow_float\n");
+          output.push_str("    add rsp, 8\n");
+        }
+        Operation::IntToFloat => {
+          output.push_str("    pop rax\n");
+          output.push_str("    cvtsi2sd xmm6, rax\n");
+          output.push_str("    movq rax, xmm6\n");
+          output.push_str("    push rax\n");
+        }
+        Operation::UnsignedIntToFloat => {
+          // 'cvtsi2sd' is signed. Values with the top bit set are halved (keeping the lowest
+          // bit for rounding), converted and doubled again.
+          output.push_str("    pop rax\n");
+          output.push_str("    mov rbx, rax\n");
+          output.push_str("    shr rbx, 1\n");
+          output.push_str("    mov r12, rax\n");
+          output.push_str("    and r12, 1\n");
+          output.push_str("    or rbx, r12\n");
+          output.push_str("    cvtsi2sd xmm6, rbx\n");
+          output.push_str("    addsd xmm6, xmm6\n");
+          output.push_str("    cvtsi2sd xmm7, rax\n");
+          output.push_str("    movq rbx, xmm6\n");
+          output.push_str("    movq r12, xmm7\n");
+          output.push_str("    test rax, rax\n");
+          output.push_str("    cmovns rbx, r12\n");
+          output.push_str("    push rbx\n");
+        }
+        Operation::FloatToInt => {
+          // truncates towards zero
+          output.push_str("    pop rax\n");
+          output.push_str("    movq xmm6, rax\n");
+          output.push_str("    cvttsd2si rax, xmm6\n");
+          output.push_str("    push rax\n");
+        }
+        Operation::FloatToUnsignedInt => {
+          // 'cvttsd2si' is signed. Values from 2^63 upwards are shifted down by 2^63 first.
+          output.push_str("    pop rax\n");
+          output.push_str("    movq xmm6, rax\n");
+          output.push_str("    cvttsd2si rbx, xmm6\n");
+          output.push_str("    mov rax, 0x43e0000000000000\n"); // 2^63
+          output.push_str("    movq xmm7, rax\n");
+          output.push_str("    subsd xmm6, xmm7\n");
+          output.push_str("    cvttsd2si r12, xmm6\n");
+          output.push_str("    btc r12, 63\n");
+          output.push_str("    xorpd xmm7, xmm7\n");
+          output.push_str("    ucomisd xmm6, xmm7\n");
+          output.push_str("    cmovae rbx, r12\n");
+          output.push_str("    push rbx\n");
+        }
+        Operation::NegFloat => {
+          // flip the sign bit
+          output.push_str("    pop rax\n");
+          output.push_str("    btc rax, 63\n");
+          output.push_str("    push rax\n");
+        }
         Operation::PushBool(b) => {
           output.push_str(format!("    push QWORD [{}]\n", b).as_str());
 
         }
-        Operation::AndBool => {
+        // keep the left value as the result if it decides it, otherwise drop it
+        Operation::ShortCircuitAnd(n) => {
+          output.push_str("    cmp QWORD [rsp], 0\n");
+          output.push_str(format!("    je END_SHORT_CIRCUIT_{}\n", n).as_str());
+          output.push_str("    add rsp, 8\n");
+        }
+        Operation::ShortCircuitOr(n) => {
+          output.push_str("    cmp QWORD [rsp], 0\n");
+          output.push_str(format!("    jne END_SHORT_CIRCUIT_{}\n", n).as_str());
+          output.push_str("    add rsp, 8\n");
+        }
+        Operation::EndShortCircuit(n) => {
+          output.push_str(format!("END_SHORT_CIRCUIT_{}:\n", n).as_str());
+        }
+        Operation::KeepComparand => {
           output.push_str("    pop rbx\n");
           output.push_str("    pop rax\n");
-          output.push_str("    and rax, rbx\n");
+          output.push_str("    push rbx\n");
+          output.push_str("    push rax\n");
+          output.push_str("    push rbx\n");
+        }
+        // pops the result of one link of the chain, the kept operand stays on the stack
+        Operation::ChainComparison(n) => {
+          output.push_str("    pop rax\n");
+          output.push_str("    test rax, rax\n");
+          output.push_str(format!("    jz CHAIN_FALSE_{}\n", n).as_str());
+        }
+        // a false link replaces the kept operand with the result
+        Operation::EndChainComparison(n) => {
+          output.push_str(format!("    jmp END_CHAIN_{}\n", n).as_str());
+          output.push_str(format!("CHAIN_FALSE_{}:\n", n).as_str());
+          output.push_str("    mov rax, [false]\n");
+          output.push_str("    mov [rsp], rax\n");
+          output.push_str(format!("END_CHAIN_{}:\n", n).as_str());
+        }
+        Operation::PushStr(n) => {
+          output.push_str(format!("    lea rax, [STR_{}]\n", n).as_str());
+          output.push_str("    push rax\n");
+        }
+        Operation::StrLen => {
+          output.push_str("    pop rax\n");
+          output.push_str("    push QWORD [rax]\n");
+        }
+        Operation::PushAddress(name) => {
+          output.push_str(format!("    lea rax, [{}]\n", name).as_str());
           output.push_str("    push rax\n");
         }
-        Operation::OrBool => {
+        Operation::PushLocalAddress(offset) => {
+          output.push_str(format!("    lea rax, [rbp - {}]\n", offset).as_str());
+          output.push_str("    push rax\n");
+        }
+        Operation::ClearLocal(offset, size) => {
+          output.push_str(format!("    lea rdi, [rbp - {}]\n", offset).as_str());
+          output.push_str(format!("    mov rcx, {}\n", size / 8).as_str());
+          output.push_str("    xor eax, eax\n");
+          output.push_str("    rep stosq\n");
+        }
+        Operation::ElementAddress(size, len) => {
+          output.push_str("    pop rax\n");
           output.push_str("    pop rbx\n");
+          output.push_str(format!("    cmp rax, {}\n", len).as_str());
+          output.push_str("    jae index_error\n");
+          output.push_str(format!("    lea rax, [rbx + rax * {}]\n", size).as_str());
+          output.push_str("    push rax\n");
+        }
+        Operation::FieldAddress(offset) => {
           output.push_str("    pop rax\n");
-          output.push_str("    or rax, rbx\n");
+          output.push_str(format!("    add rax, {}\n", offset).as_str());
+          output.push_str("    push rax\n");
+        }
+        Operation::LoadElement(typ) => {
+          output.push_str("    pop rax\n");
+          Compiler::load_int(&mut output, "rax", typ);
+        }
+        Operation::StoreElement(typ) => {
+          output.push_str("    pop rbx\n");
+          Compiler::store_int(&mut output, "rbx", typ);
+        }
+        Operation::NotBool => {
+          output.push_str("    pop rax\n");
+          output.push_str("    xor rax, 1\n");
           output.push_str("    push rax\n");
         }
         Operation::PrintInt => {
           output.push_str("    pop rdi\n");
           output.push_str("    call print_int\n");
         },
-        Operation::LoadInt(addr) => {
-          output.push_str(format!("    mov rax, [{}]\n", addr).as_str());
-          output.push_str("    push rax\n");
+        Operation::PrintSignedInt => {
+          output.push_str("    pop rdi\n");
+          output.push_str("    call print_signed_int\n");
+        },
+        Operation::PrintFloat(decimals, trim) => {
+          output.push_str("    pop rdi\n");
+          output.push_str(format!("    mov rsi, {}\n", decimals).as_str());
+          output.push_str(format!("    mov rdx, {}\n", *trim as u8).as_str());
+          output.push_str("    call print_float\n");
+        },
+        Operation::PrintFloatGeneral(digits) => {
+          output.push_str("    pop rdi\n");
+          output.push_str(format!("    mov rsi, {}\n", digits).as_str());
+          output.push_str("    call print_float_general\n");
+        },
+        Operation::PrintBool => {
+          output.push_str("    pop rdi\n");
+          output.push_str("    call print_bool\n");
+        },
+        Operation::PrintStr => {
+          output.push_str("    pop rsi\n");
+          output.push_str("    mov rdx, [rsi]\n");
+          output.push_str("    add rsi, 8\n");
+          output.push_str("    mov rax, 1\n");
+          output.push_str("    mov rdi, 1\n");
+          output.push_str("    syscall\n");
         },
-        Operation::StoreInt(addr) => {
+        Operation::PrintChar(ch) => {
+          output.push_str(format!("    push {}\n", ch).as_str());
+          output.push_str("    mov rax, 1\n");
+          output.push_str("    mov rdi, 1\n");
+          output.push_str("    mov rsi, rsp\n");
+          output.push_str("    mov rdx, 1\n");
+          output.push_str("    syscall\n");
           output.push_str("    pop rax\n");
-          output.push_str(format!("    mov [{}], rax\n", addr).as_str());
         },
+        Operation::LoadInt(addr, typ) => Compiler::load_int(&mut output, addr, typ),
+        Operation::StoreInt(addr, typ) => Compiler::store_int(&mut output, addr, typ),
         Operation::If(n) => {
           output.push_str("    pop rax\n");
           output.push_str("    cmp rax, 0\n");
@@ -297,10 +1175,19 @@ impl Compiler {
           output.push_str("    cmp rax, 0\n");
           output.push_str(format!("    je END_WHILE_{}\n", n).as_str());
         }
+        Operation::StepWhile(n) => {
+          output.push_str(format!("STEP_WHILE_{}:\n", n).as_str());
+        }
         Operation::EndWhile(n) => {
           output.push_str(format!("    jmp WHILE_{}\n", n).as_str());
           output.push_str(format!("END_WHILE_{}:\n", n).as_str());
         }
+        Operation::Break(n) => {
+          output.push_str(format!("    jmp END_WHILE_{}\n", n).as_str());
+        }
+        Operation::Continue(n) => {
+          output.push_str(format!("    jmp STEP_WHILE_{}\n", n).as_str());
+        }
         Operation::PopStack => {
           output.push_str("    pop rax\n");
         }
@@ -315,14 +1202,15 @@ impl Compiler {
           output.push_str("    pop rbp\n");
           output.push_str("    ret\n");
         }
-        Operation::FunctionCall(name, _) => {
+        Operation::FunctionCall(name, arguments_size) => {
           output.push_str(format!("    call {}\n", name).as_str());
+          if *arguments_size > 0 {
+            output.push_str(format!("    add rsp, {}\n", arguments_size).as_str());
+          }
         }
         Operation::ReserveParameters(size) => {
             output.push_str(format!("    sub rsp, {size}\n").as_str());
         }
-        Operation::LiteralFloat => todo!(),
-        Operation::SwtichRegisterFloat => todo!(),
         Operation::StoreFloat(addr) => {
           output.push_str("    pop rax\n");
           output.push_str(format!("    mov [{}], rax\n", addr).as_str());
@@ -331,24 +1219,19 @@ impl Compiler {
           output.push_str(format!("    mov rax, QWORD [{}]\n", addr).as_str());
           output.push_str("    push rax\n");
         }
-        Operation::SysVIntegerArguemtnPreparation(i) => {
-          output.push_str(format!("    pop {}\n", INTEGER_ARGUMENT_ORDDER[*i]).as_str());
+        Operation::StoreLocal(offset, typ) => Compiler::store_int(&mut output, &format!("rbp - {offset}"), typ),
+        Operation::LoadLocal(offset, typ) => Compiler::load_int(&mut output, &format!("rbp - {offset}"), typ),
+        Operation::SysVIntegerArguemtnPreparation(i, offset) => {
+          output.push_str(format!("    mov {}, QWORD [rsp + {}]\n", INTEGER_ARGUMENT_ORDDER[*i], offset).as_str());
         }
-        Operation::SysVIntegerSaveArgumentAfterCall(i, offset) => {
-          output.push_str(format!("    mov QWORD [rbp - {}], {}\n", offset, INTEGER_ARGUMENT_ORDDER[*i]).as_str());
+        Operation::SysVIntegerSaveArgumentAfterCall(i, offset, typ) => {
+          let width = Compiler::width_index(typ);
+          output.push_str(format!("    mov {} [rbp - {}], {}\n", MEMORY_SIZES[width], offset, INTEGER_ARGUMENT_PARTS[*i][width]).as_str());
         }
-        Operation::SysVIntegerPrameterLoad(offset) => {
-          output.push_str(format!("    mov rax, QWORD [rbp - {}]\n", offset).as_str());
-          output.push_str("    push rax\n");
-
-        }
-        Operation::SysVIntegerPrameterStore(offset) => {
-          output.push_str("    pop rax\n");
-          output.push_str(format!("    mov QWORD [rbp - {}], rax\n", offset).as_str());
-        }
-        Operation::SysVSSEArgumentPreparation(i) => {
-          output.push_str("    pop rax\n");
-          output.push_str(format!("    movq {}, rax\n", SSE_ARRGUMENT_ORDER[*i]).as_str());
+        Operation::SysVIntegerPrameterLoad(offset, typ) => Compiler::load_int(&mut output, &format!("rbp - {offset}"), typ),
+        Operation::SysVIntegerPrameterStore(offset, typ) => Compiler::store_int(&mut output, &format!("rbp - {offset}"), typ),
+        Operation::SysVSSEArgumentPreparation(i, offset) => {
+          output.push_str(format!("    movq {}, QWORD [rsp + {}]\n", SSE_ARRGUMENT_ORDER[*i], offset).as_str());
         }
         Operation::SysVSSESaveArgumentAfterCall(i, offset) => {
           output.push_str(format!("    movq [rbp - {}], {}\n", offset, SSE_ARRGUMENT_ORDER[*i]).as_str());
@@ -361,15 +1244,11 @@ impl Compiler {
           output.push_str("    pop rax\n");
           output.push_str(format!("    mov QWORD [rbp - {}], rax\n", offset).as_str());
         }
-        Operation::SysVMemoryArgumentPreparation(_) => {},
-        Operation::SysVMemoryParameterLoad(offset) => {
-          output.push_str(format!("    mov rax, QWORD [rbp + 16 + {}]\n", offset).as_str());
-          output.push_str("    push rax\n");
-        },
-        Operation::SysVMemoryParameterStore(offset) => {
-          output.push_str("    pop rax\n");
-          output.push_str(format!("    mov QWORD [rbp + 16 + {}], rax\n", offset).as_str());
-        },
+        Operation::SysVMemoryArgumentPreparation(offset) => {
+          output.push_str(format!("    push QWORD [rsp + {}]\n", offset).as_str());
+        }
+        Operation::SysVMemoryParameterLoad(offset, typ) => Compiler::load_int(&mut output, &format!("rbp + 16 + {offset}"), typ),
+        Operation::SysVMemoryParameterStore(offset, typ) => Compiler::store_int(&mut output, &format!("rbp + 16 + {offset}"), typ),
         Operation::SysVIntegerReturn => {
           output.push_str("pop rax\n");
         }
@@ -0,0 +1,102 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+// Location of a diagnostic in the source file.
+// 'loc' is (row, col), both starting at 1. 'len' is the number of chars to underline.
+#[derive(Debug, PartialEq, Clone, Copy, PartialOrd)]
+pub struct Span {
+    pub loc: (usize, usize),
+    pub len: usize,
+}
+
+impl Span {
+    pub fn new(loc: (usize, usize), len: usize) -> Self {
+        Span { loc, len }
+    }
+
+    pub fn at(loc: (usize, usize)) -> Self {
+        Span { loc, len: 1 }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: String,
+    pub span: Span,
+    pub message: String,
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(file: &str, span: Span, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            file: file.to_string(),
+            span,
+            message: message.into(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    // Renders the diagnostic together with the offending source line:
+    //
+    // error: Expected ':' after while expression.
+    //  --> while.py:2:12
+    //   |
+    // 2 | while x > 0
+    //   |            ^
+    pub fn render(&self, source: &str) -> String {
+        let (row, col) = self.span.loc;
+        let gutter = row.to_string().len();
+        let pad = " ".repeat(gutter);
+
+        let mut out = String::new();
+        out.push_str(&format!("{}: {}\n", self.severity, self.message));
+        out.push_str(&format!("{pad}--> {}:{}:{}\n", self.file, row, col));
+        if let Some(line) = source.lines().nth(row.saturating_sub(1)) {
+            let offset = line.chars().take(col.saturating_sub(1)).map(|ch| if ch == '\t' { '\t' } else { ' ' }).collect::<String>();
+            out.push_str(&format!("{pad} |\n"));
+            out.push_str(&format!("{row} | {line}\n"));
+            out.push_str(&format!("{pad} | {offset}{}\n", "^".repeat(self.span.len.max(1))));
+        }
+        for note in &self.notes {
+            out.push_str(&format!("{pad} = note: {note}\n"));
+        }
+        out
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (row, col) = self.span.loc;
+        write!(f, "{}:{}:{}: {}: {}", self.file, row, col, self.severity, self.message)?;
+        for note in &self.notes {
+            write!(f, "\n    note: {note}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
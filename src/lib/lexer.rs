@@ -1,12 +1,14 @@
+use std::mem;
 use std::str::Chars;
 use std::iter::Peekable;
-use crate::token::{match_builtin_functions, match_keywords, LocToken, Token, OPERATOR_MAP, OPERATOR_SYMBOLS};
+use crate::diagnostic::{Diagnostic, Span};
+use crate::token::{match_builtin_functions, match_keywords, FStringPart, LocToken, Token, OPERATOR_MAP, OPERATOR_SYMBOLS};
 
 pub struct Lexer<'a> {
     input: Peekable<Chars<'a>>,
     last_token: Token,
     position: (usize, usize),
-    consumption_length: usize,
+    token_start: (usize, usize),
     file_name: String,
 }
 
@@ -15,87 +17,113 @@ impl<'a> Lexer<'a> {
         Lexer {
             input: input.chars().peekable(),
             last_token: Token::EOF,
-            position: (1, 0),
-            consumption_length: 0,
+            position: (1, 1),
+            token_start: (1, 1),
             file_name,
         }
     }
 
-    fn increment_col(&mut self, n: usize) {
-        let (row, col) = self.position;
-        self.position = (row, col+n);
+    // For source that is embedded in an other token, like the expressions of an f-string
+    pub fn starting_at(mut self, position: (usize, usize)) -> Self {
+        self.position = position;
+        self.token_start = position;
+        self
     }
 
-    fn increment_row(&mut self) {
-        let (row, _) = self.position;
-        self.position = (row+1, 0);
+    fn error(&self, msg: impl Into<String>) -> Diagnostic {
+        let len = if self.position.0 == self.token_start.0 {
+            self.position.1 - self.token_start.1
+        } else {
+            1
+        };
+        Diagnostic::error(&self.file_name, Span::new(self.token_start, len), msg)
     }
 
-    fn find_next_token(&mut self) -> Token {
+    // Consumes one char and keeps track of the current row and column.
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.input.next();
+        let (row, col) = self.position;
+        match ch {
+            Some('\n') => self.position = (row + 1, 1),
+            Some(_) => self.position = (row, col + 1),
+            None => {}
+        }
+        ch
+    }
+
+    fn find_next_token(&mut self) -> Result<Token, Diagnostic> {
+        self.token_start = self.position;
         if let Some(&ch) = self.input.peek() {
             self.last_token = match ch {
                 ' ' | '\t' => {
                     let n = self.consume_whitespace();
                     if self.last_token == Token::Newline {
-                        self.consumption_length += n;
                         Token::Indent(n)
                     } else {
-                        self.increment_col(n);
-                        self.find_next_token()
+                        self.find_next_token()?
                     }
                 }
                 '\n' => {
-                    self.input.next();
+                    self.bump();
                     Token::Newline
 
                 }
                 '(' => {
-                    self.input.next();
+                    self.bump();
                     Token::LParen
                 }
                 ')' => {
-                    self.input.next();
+                    self.bump();
                     Token::RParen
                 }
-                '+' | '-' | '*' | '/' | '=' | '>' | '<' | '&' | '|' => self.consume_operator(),
-                '0'..='9' => self.consume_number(),
-                'a'..='z' | 'A'..='Z' | '_' => self.consume_identifier(),
+                '[' => {
+                    self.bump();
+                    Token::LBracket
+                }
+                ']' => {
+                    self.bump();
+                    Token::RBracket
+                }
+                '+' | '-' | '*' | '/' | '=' | '!' | '>' | '<' | '&' | '|' | '~' | '%' | '^' => self.consume_operator()?,
+                '0'..='9' => self.consume_number()?,
+                '\'' | '"' => self.consume_string()?,
+                'a'..='z' | 'A'..='Z' | '_' => self.consume_identifier()?,
                 ',' => {
-                    self.input.next();
+                    self.bump();
                     Token::Comma
                 }
+                '.' => {
+                    self.bump();
+                    Token::Dot
+                }
                 ':' => {
-                    self.input.next();
+                    self.bump();
                     Token::Colon
                 }
                 '#' => {
                     while self.input.peek() != Some(&'\n') && self.input.peek().is_some() {
-                        self.input.next();
+                        self.bump();
                     }
-                    self.find_next_token()
+                    self.find_next_token()?
                 }
                 unhandled => {
-                    self.input.next();
-                    let (row, col) = self.position;
-                    eprintln!("{}:{}:{}", self.file_name, row, col);
-                    panic!("Can not deal with char '{}'", unhandled);
+                    self.bump();
+                    return Err(self.error(format!("Can not deal with char '{}'", unhandled)));
                 }
             };
-            return self.last_token.clone();
+            return Ok(self.last_token.clone());
         }
 
-        Token::EOF
+        Ok(Token::EOF)
     }
 
-    pub fn next_token(&mut self) -> LocToken {
-        if self.last_token == Token::Newline {
-            self.increment_row();
-        }
-        self.consumption_length = 1;
-        let t = self.find_next_token();
-        let res = (self.position, t);
-        self.increment_col(self.consumption_length);
-        res
+    pub fn next_token(&mut self) -> Result<LocToken, Diagnostic> {
+        let t = self.find_next_token()?;
+        let len = match t {
+            Token::Newline | Token::EOF => 1,
+            _ => self.position.1 - self.token_start.1,
+        };
+        Ok((Span::new(self.token_start, len), t))
     }
 
     fn consume_whitespace(&mut self) -> usize {
@@ -103,7 +131,7 @@ impl<'a> Lexer<'a> {
         while let Some(&ch) = self.input.peek() {
             if ch == ' ' || ch == '\t' {
                 count += 1;
-                self.input.next();
+                self.bump();
             } else {
                 return count;
             }
@@ -111,80 +139,218 @@ impl<'a> Lexer<'a> {
         count
     }
 
-    fn consume_number(&mut self) -> Token {
+    fn consume_number(&mut self) -> Result<Token, Diagnostic> {
         let mut num_str = String::new();
         let mut saw_dot = false;
         while let Some(&ch) = self.input.peek() {
             if ch.is_ascii_digit() {
                 num_str.push(ch);
-                self.input.next();
+                self.bump();
             }
             else if ch == '.' {
                 if saw_dot {
-                    panic!("Error parsing number. Floats can't have more then one '.'");
+                    self.bump();
+                    return Err(self.error("Error parsing number. Floats can't have more then one '.'"));
                 }
                 saw_dot = true;
                 num_str.push(ch);
-                self.input.next();
+                self.bump();
             }
             else {
                 break;
             }
         }
-        self.consumption_length += num_str.len();
         if saw_dot {
-            Token::Float(num_str)
+            Ok(Token::Float(num_str))
         }
         else {
-            Token::Integer(num_str)
+            Ok(Token::Integer(num_str))
         }
     }
 
-    fn consume_identifier(&mut self) -> Token {
+    // Strings are enclosed in matching ' or " and end on the same line
+    fn consume_string(&mut self) -> Result<Token, Diagnostic> {
+        let Some(quote) = self.bump() else {
+            return Err(self.error("Expected a string literal."));
+        };
+        let mut value = String::new();
+        loop {
+            match self.input.peek() {
+                None | Some('\n') => return Err(self.error("Unterminated string literal")),
+                Some(&ch) if ch == quote => {
+                    self.bump();
+                    return Ok(Token::String(value));
+                }
+                Some('\\') => value.push(self.consume_escape()?),
+                Some(&ch) => {
+                    value.push(ch);
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn consume_escape(&mut self) -> Result<char, Diagnostic> {
+        self.bump(); // consume '\'
+        match self.bump() {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some('0') => Ok('\0'),
+            Some('\\') => Ok('\\'),
+            Some('\'') => Ok('\''),
+            Some('"') => Ok('"'),
+            Some(ch) => Err(self.error(format!("Unknown escape sequence '\\{}'", ch))),
+            None => Err(self.error("Unterminated string literal")),
+        }
+    }
+
+    // f"x={x} y={y:.3}": text and expressions with an optional format spec. '{{' and '}}' are
+    // the braces themselves. The expressions can not contain strings.
+    fn consume_fstring(&mut self) -> Result<Token, Diagnostic> {
+        let Some(quote) = self.bump() else {
+            return Err(self.error("Expected an f-string."));
+        };
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        loop {
+            match self.input.peek() {
+                None | Some('\n') => return Err(self.error("Unterminated string literal")),
+                Some(&ch) if ch == quote => {
+                    self.bump();
+                    break;
+                }
+                Some('\\') => text.push(self.consume_escape()?),
+                Some('}') => {
+                    self.bump();
+                    if self.bump() != Some('}') {
+                        return Err(self.error("Single '}' in an f-string. Use '}}' for the brace itself"));
+                    }
+                    text.push('}');
+                }
+                Some('{') => {
+                    self.bump();
+                    if self.input.peek() == Some(&'{') {
+                        self.bump();
+                        text.push('{');
+                        continue;
+                    }
+                    if !text.is_empty() {
+                        parts.push(FStringPart::Text(mem::take(&mut text)));
+                    }
+                    parts.push(self.consume_fstring_expression(quote)?);
+                }
+                Some(&ch) => {
+                    text.push(ch);
+                    self.bump();
+                }
+            }
+        }
+        if !text.is_empty() {
+            parts.push(FStringPart::Text(text));
+        }
+        Ok(Token::FString(parts))
+    }
+
+    // everything up to the closing '}', the opening one is already consumed
+    fn consume_fstring_expression(&mut self, quote: char) -> Result<FStringPart, Diagnostic> {
+        let position = self.position;
+        let mut source = String::new();
+        let mut spec = None;
+        let mut depth = 0;
+        loop {
+            match self.input.peek() {
+                None | Some('\n') => return Err(self.error("Expected '}' in the f-string")),
+                Some(&ch) if ch == quote => return Err(self.error("Expected '}' in the f-string")),
+                Some('\'' | '"') => {
+                    return Err(self.error("Strings are not allowed in the expressions of an f-string"))
+                }
+                Some('}') => {
+                    self.bump();
+                    break;
+                }
+                Some(':') if depth == 0 => {
+                    self.bump();
+                    let mut format = String::new();
+                    while let Some(&ch) = self.input.peek() {
+                        if ch == '}' || ch == '\n' || ch == quote {
+                            break;
+                        }
+                        format.push(ch);
+                        self.bump();
+                    }
+                    spec = Some(format);
+                }
+                Some(&ch) => {
+                    match ch {
+                        '(' => depth += 1,
+                        ')' => depth -= 1,
+                        _ => {}
+                    }
+                    source.push(ch);
+                    self.bump();
+                }
+            }
+        }
+        if source.trim().is_empty() {
+            return Err(self.error("Empty expression in the f-string"));
+        }
+        Ok(FStringPart::Expression(source, spec, position))
+    }
+
+    fn consume_identifier(&mut self) -> Result<Token, Diagnostic> {
         let mut id_str = String::new();
         while let Some(&ch) = self.input.peek() {
             if ch.is_alphanumeric() || ch == '_' {
                 id_str.push(ch);
-                self.input.next();
+                self.bump();
             } else {
                 break;
             }
         }
-        self.consumption_length += id_str.len();
 
         // match id_str.as_str() {
         //     "def" | "if" | "else" => Token::Keyword(id_str),
         //     "print"
         //     _ => Token::Identifier(id_str),
         // }
-        if let Some(s) = match_keywords(&id_str) {
+        let token = if id_str == "f" && matches!(self.input.peek(), Some('\'' | '"')) {
+            self.consume_fstring()?
+        }
+        else if let Some(s) = match_keywords(&id_str) {
             Token::Keyword(s)
         }
         else if let Some(s) = match_builtin_functions(&id_str) {
             Token::Builtin(s)
         }
+        // 'and', 'or' and 'is' are operators spelled like identifiers
+        else if let Some(op_type) = OPERATOR_MAP.get(id_str.as_str()) {
+            Token::Operator(op_type.clone(), id_str)
+        }
         else {
             Token::Identifier(id_str)
-        }
+        };
+        Ok(token)
     }
 
-    fn consume_operator(&mut self) -> Token {
+    fn consume_operator(&mut self) -> Result<Token, Diagnostic> {
         let mut op_str = String::new();
         while let Some(&ch) = self.input.peek() {
-            if OPERATOR_SYMBOLS.contains(&ch) {
+            // only keep going while it can still become a known operator, so '2*-3' is '*' and '-'
+            let candidate = format!("{op_str}{ch}");
+            if OPERATOR_SYMBOLS.contains(&ch) && (op_str.is_empty() || OPERATOR_MAP.keys().any(|op| op.starts_with(&candidate))) {
                 op_str.push(ch);
-                self.input.next();
+                self.bump();
             }
             else {
                 break
             }
         }
-        self.consumption_length += op_str.len();
 
         let Some(op_type) = OPERATOR_MAP.get(&op_str) else {
-            panic!("Uknown operator '{}'", op_str);
+            return Err(self.error(format!("Uknown operator '{}'", op_str)));
         };
-        Token::Operator(op_type.clone(), op_str)
+        Ok(Token::Operator(op_type.clone(), op_str))
         // if op_str.as_str() == "=" {
         //     Token::Assignment
         // }
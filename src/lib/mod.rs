@@ -9,6 +9,7 @@ pub mod builder;
 pub mod compiler;
 pub mod os;
 pub mod type_checker;
+pub mod diagnostic;
 
 pub trait Parameters<T> {
   fn add(&mut self, name: &str, value_type: &PrimitiveTypes);
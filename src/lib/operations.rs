@@ -1,35 +1,95 @@
 
 use std::{ops::{Deref, DerefMut}, rc::Rc};
 
-use crate::ast::{ASTNode, ConstLiteral, PrimitiveTypes};
+use crate::ast::{ConstLiteral, PrimitiveTypes};
 
 #[derive(Debug)]
 pub enum Operation {
   PushInt(String),
   AddInt,
   MultInt,
+  MultSignedInt,
   MinusInt,
   DivInt,
+  DivSignedInt,
+  // '//' and '%' round towards negative infinity like Python, unsigned ones use DivInt
+  FloorDivSignedInt,
+  ModInt,
+  ModSignedInt,
+  PowerInt,
+  PowerSignedInt,
+  NegInt,
+  BitNotInt,
+  BitAndInt,
+  BitOrInt,
+  BitXorInt,
+  ShiftLeftInt,
+  ShiftRightInt,
+  ShiftRightSignedInt,
+  Truncate(PrimitiveTypes), // cuts a result back to the width of the type
+  // signed
   GreaterInt,
+  GreaterEqualInt,
   LessInt,
+  LessEqualInt,
+  // unsigned
+  AboveInt,
+  AboveEqualInt,
+  BelowInt,
+  BelowEqualInt,
 
   PushFloat(String),
-  LiteralFloat,
-  SwtichRegisterFloat,
   AddFloat,
   MultFloat,
   MinusFloat,
   DivFloat,
+  FloorDivFloat,
+  ModFloat,
+  PowerFloat,
+  NegFloat,
+  IntToFloat,
+  UnsignedIntToFloat,
+  FloatToInt,
+  FloatToUnsignedInt,
+  EqualFloat,
+  NotEqualFloat,
+  GreaterFloat,
+  GreaterEqualFloat,
+  LessFloat,
+  LessEqualFloat,
 
   PushBool(String),
-  AndBool,
-  OrBool,
+  ShortCircuitAnd(usize),
+  ShortCircuitOr(usize),
+  EndShortCircuit(usize),
+  KeepComparand, // a b -> b a b
+  ChainComparison(usize),
+  EndChainComparison(usize),
+  NotBool,
+
+  PushStr(usize), // address of the n-th string of the program
+  StrLen,
+
+  PushAddress(String),
+  PushLocalAddress(usize),
+  ClearLocal(usize, usize), // (offset, size)
+  ElementAddress(usize, usize), // (element size, length): address index -> address of the element
+  FieldAddress(usize), // (offset): address -> address of the field
+  LoadElement(PrimitiveTypes),
+  StoreElement(PrimitiveTypes), // value address ->
 
   PopStack,
 
   PrintInt,
+  PrintSignedInt,
+  PrintFloat(usize, bool), // (decimals, drop trailing zeros)
+  PrintFloatGeneral(usize), // significant digits
+  PrintBool,
+  PrintStr,
+  PrintChar(u8),
 
   EqualInt,
+  NotEqualInt,
 
   If(usize),
   Else(usize),
@@ -37,31 +97,37 @@ pub enum Operation {
 
   While(usize),
   CondWhile(usize),
+  StepWhile(usize), // 'continue' jumps here
   EndWhile(usize),
+  Break(usize),
+  Continue(usize),
 
-  StoreInt(String),
-  LoadInt(String),
+  // integers and bools are stored with the width of their type
+  StoreInt(String, PrimitiveTypes),
+  LoadInt(String, PrimitiveTypes),
   StoreFloat(String),
   LoadFloat(String),
+  StoreLocal(usize, PrimitiveTypes), // rbp relative offset
+  LoadLocal(usize, PrimitiveTypes),
 
 
   // System V operations
-  SysVIntegerArguemtnPreparation(usize),
-  SysVIntegerSaveArgumentAfterCall(usize, usize), // (reg index, stack offset)
-  SysVIntegerPrameterLoad(usize),
-  SysVIntegerPrameterStore(usize),
-  SysVSSEArgumentPreparation(usize),
+  SysVIntegerArguemtnPreparation(usize, usize), // (reg index, rsp relative offset)
+  SysVIntegerSaveArgumentAfterCall(usize, usize, PrimitiveTypes), // (reg index, stack offset, type)
+  SysVIntegerPrameterLoad(usize, PrimitiveTypes),
+  SysVIntegerPrameterStore(usize, PrimitiveTypes),
+  SysVSSEArgumentPreparation(usize, usize),
   SysVSSESaveArgumentAfterCall(usize, usize),
   SysVSSEParameterLoad(usize),
   SysVSSEParameterStore(usize),
   SysVMemoryArgumentPreparation(usize),
-  SysVMemoryParameterLoad(usize),
-  SysVMemoryParameterStore(usize),
+  SysVMemoryParameterLoad(usize, PrimitiveTypes),
+  SysVMemoryParameterStore(usize, PrimitiveTypes),
 
   BeginFunction(String),
   ReserveParameters(usize),
   EndFunction(String),
-  FunctionCall(String, usize),
+  FunctionCall(String, usize), // (name, bytes of arguments to drop after the call)
   SysVIntegerReturn,
   SysVSSEReturn,
   SysVPushIntegerReturn,
@@ -105,8 +171,9 @@ pub struct ConstVariable(pub String, pub PrimitiveTypes, pub ConstLiteral);
 pub struct Program {
   pub function_defs: Vec<Operation>,
   pub main: Vec<Operation>,
-  pub vars: Vec<String>,
+  pub vars: Vec<(String, usize)>, // (name, size)
   pub consts: Vec<ConstVariable>,
+  pub strings: Vec<String>,
   pub target: OperationsType,
 }
 
@@ -123,6 +190,7 @@ impl Program {
       main: Vec::new(),
       vars: Vec::new(),
       consts: Vec::new(),
+      strings: Vec::new(),
       target: OperationsType::Main,
     }
   }
@@ -23,7 +23,7 @@ pub trait Parameters<T> : Debug + Clone
 where T: Parameter {
   fn add_parameters(&mut self, parameters: &[(String, PrimitiveTypes)]);
   fn translate_save_arguments(&self, index: usize, operations: &mut Program);
-  fn trnslate_caller_argument(&self, index: usize, operations: &mut Program);
+  fn translate_caller_arguments(&self, operations: &mut Program) -> usize;
 }
 
 impl Parameters<systemv::Parameter> for systemv::SystemV {
@@ -32,8 +32,8 @@ impl Parameters<systemv::Parameter> for systemv::SystemV {
     self.translate_save_arguments(index, operations);
   }
 
-  fn trnslate_caller_argument(&self, index: usize, operations: &mut Program) {
-    self.trnslate_caller_argument(index, operations);
+  fn translate_caller_arguments(&self, operations: &mut Program) -> usize {
+    self.translate_caller_arguments(operations)
   }
 
   fn add_parameters(&mut self, parameters: &[(String, PrimitiveTypes)]) {
@@ -1,4 +1,4 @@
-use std::{ops::Deref, rc::Rc};
+use std::{collections::HashMap, ops::Deref, rc::Rc};
 
 use crate::{ast::PrimitiveTypes, operations::{Operation, Program}};
 
@@ -20,20 +20,20 @@ pub struct Parameter {
   // arg_index: usize,
   class: ParameterClass,
   class_index: usize,
-  // value_type: PrimitiveTypes,
+  value_type: PrimitiveTypes,
 }
 
 impl Parameter {
   pub fn translate_store(&self, operations: &mut Program) {
     match self.class {
       ParameterClass::Integer(offset) => {
-        operations.push(Operation::SysVIntegerPrameterStore(offset));
+        operations.push(Operation::SysVIntegerPrameterStore(offset, self.value_type.clone()));
       }
       ParameterClass::Sse(offset) => {
         operations.push(Operation::SysVSSEParameterStore(offset));
       }
       ParameterClass::Memory(offset) => {
-        operations.push(Operation::SysVMemoryParameterStore(offset));
+        operations.push(Operation::SysVMemoryParameterStore(offset, self.value_type.clone()));
       }
     }
   }
@@ -41,18 +41,46 @@ impl Parameter {
   pub fn translate_load(&self, operations: &mut Program) {
     match self.class {
       ParameterClass::Integer(offset) => {
-        operations.push(Operation::SysVIntegerPrameterLoad(offset));
+        operations.push(Operation::SysVIntegerPrameterLoad(offset, self.value_type.clone()));
       }
       ParameterClass::Sse(offset) => {
         operations.push(Operation::SysVSSEParameterLoad(offset));
       }
       ParameterClass::Memory(offset) => {
-        operations.push(Operation::SysVMemoryParameterLoad(offset));
+        operations.push(Operation::SysVMemoryParameterLoad(offset, self.value_type.clone()));
       }
     }
   }
 }
 
+// A variable declared inside a function body. It lives in the stack frame of the function
+// right below the saved parameters, so every call (including recursive ones) gets its own copy.
+#[derive(Debug, Clone)]
+pub struct Local {
+  offset: usize,
+  value_type: PrimitiveTypes,
+}
+
+impl Local {
+  pub fn translate_store(&self, operations: &mut Program) {
+    operations.push(Operation::StoreLocal(self.offset, self.value_type.clone()));
+  }
+
+  pub fn translate_load(&self, operations: &mut Program) {
+    operations.push(Operation::LoadLocal(self.offset, self.value_type.clone()));
+  }
+
+  // Arrays are used through the address of their first element
+  pub fn translate_address(&self, operations: &mut Program) {
+    operations.push(Operation::PushLocalAddress(self.offset));
+  }
+
+  // The stack frame is not zeroed like '.bss'
+  pub fn translate_clear(&self, operations: &mut Program) {
+    operations.push(Operation::ClearLocal(self.offset, self.value_type.slot_size()));
+  }
+}
+
 #[derive(Debug, Clone)]
  pub struct SystemV {
   parameters: Vec<Parameter>,
@@ -66,6 +94,7 @@ impl Parameter {
   memory_parameters: Vec<Parameter>,
   memory_size: usize,
   stack_reserve_size: usize,
+  locals: HashMap<String, Local>,
   return_type: Option<PrimitiveTypes>,
   func_name: Rc<String>,
  }
@@ -91,6 +120,7 @@ impl SystemV {
       memory_parameters: Vec::new(),
       memory_size: 0,
       stack_reserve_size: 0,
+      locals: HashMap::new(),
       return_type: None,
       func_name: name,
     }
@@ -104,23 +134,35 @@ impl SystemV {
     self.stack_reserve_size
   }
 
+  // Every integer width is passed in a full 64 bit register (or stack slot), already sign or
+  // zero extended. The callee only looks at the bytes of its type.
   fn add(&mut self, value_type: &PrimitiveTypes) {
     let parameter: Parameter;
     match value_type {
       PrimitiveTypes::Bool |
-      PrimitiveTypes::U64 => {
+      PrimitiveTypes::Str |
+      PrimitiveTypes::U64 |
+      PrimitiveTypes::U32 |
+      PrimitiveTypes::U16 |
+      PrimitiveTypes::U8 |
+      PrimitiveTypes::I64 |
+      PrimitiveTypes::I32 |
+      PrimitiveTypes::I16 |
+      PrimitiveTypes::I8 => {
         if self.integer_parameters.len() < 6 {
+          self.stack_reserve_size += 8;
           parameter = Parameter {
             class: ParameterClass::Integer(self.stack_reserve_size),
             class_index: self.integer_parameters.len(),
+            value_type: value_type.clone(),
           };
-          self.stack_reserve_size += 8;
           self.integer_parameters.push(parameter.clone());
         }
         else {
           parameter = Parameter {
             class: ParameterClass::Memory(self.memory_size),
             class_index: self.memory_parameters.len(),
+            value_type: value_type.clone(),
           };
           self.memory_parameters.push(parameter.clone());
           self.memory_size += 8;
@@ -128,17 +170,19 @@ impl SystemV {
       }
       PrimitiveTypes::F64 => {
         if self.sse_parameter.len() < 8 {
+          self.stack_reserve_size += 8;
           parameter = Parameter {
             class: ParameterClass::Sse(self.stack_reserve_size),
             class_index: self.sse_parameter.len(),
+            value_type: value_type.clone(),
           };
-          self.stack_reserve_size += 8;
           self.sse_parameter.push(parameter.clone());
         }
         else {
           parameter = Parameter {
             class: ParameterClass::Memory(self.memory_size),
             class_index: self.memory_parameters.len(),
+            value_type: value_type.clone(),
           };
           self.memory_parameters.push(parameter.clone());
           self.memory_size += 8;
@@ -149,11 +193,24 @@ impl SystemV {
       PrimitiveTypes::Float |
       PrimitiveTypes::Integer |
       PrimitiveTypes::Void |
-      PrimitiveTypes::COUNT => panic!(),
+      PrimitiveTypes::Array(_, _) |
+      PrimitiveTypes::Struct(_, _) |
+      PrimitiveTypes::COUNT => unreachable!("the parser only allows single values as parameters, but got '{}'", value_type),
     }
     self.parameters.push(parameter);
   }
 
+  // Locals have to be added after all parameters, because they are placed below them.
+  pub fn add_local(&mut self, name: &str, value_type: &PrimitiveTypes) {
+    self.stack_reserve_size += value_type.slot_size();
+    let local = Local { offset: self.stack_reserve_size, value_type: value_type.clone() };
+    self.locals.insert(name.to_string(), local);
+  }
+
+  pub fn get_local(&self, name: &str) -> Option<&Local> {
+    self.locals.get(name)
+  }
+
   pub fn add_return(&mut self, return_type: Option<PrimitiveTypes>) {
     self.return_type = return_type;
   }
@@ -167,36 +224,7 @@ impl SystemV {
   }
 
   pub fn add_parameters(&mut self, parameters: &[(String, PrimitiveTypes)]) {
-    let mut memory_class: Vec<&(String, PrimitiveTypes)> = Vec::new();
-    for p @ (_name, value_type) in parameters {
-      match value_type {
-        PrimitiveTypes::Bool |
-        PrimitiveTypes::U64 => {
-          if self.integer_parameters.len() < 6 {
-            self.add(value_type);
-          }
-          else {
-            memory_class.push(p);
-          }
-        }
-        PrimitiveTypes::F64 => {
-          if self.sse_parameter.len() < 8 {
-            self.add(value_type);
-          }
-          else {
-            memory_class.push(p);
-          }
-        }
-
-        PrimitiveTypes::Number |
-        PrimitiveTypes::Float |
-        PrimitiveTypes::Integer |
-        PrimitiveTypes::Void |
-        PrimitiveTypes::COUNT => panic!()
-      }
-      println!("{}", self.stack_reserve_size);
-    }
-    for (_name, value_type) in memory_class.iter().rev() {
+    for (_name, value_type) in parameters {
       self.add(value_type);
     }
   }
@@ -207,7 +235,7 @@ impl SystemV {
     };
     match parameter.class {
       ParameterClass::Integer(offset) => {
-        operations.push(Operation::SysVIntegerSaveArgumentAfterCall(parameter.class_index, offset));
+        operations.push(Operation::SysVIntegerSaveArgumentAfterCall(parameter.class_index, offset, parameter.value_type.clone()));
       }
       ParameterClass::Sse(offset) => {
         operations.push(Operation::SysVSSESaveArgumentAfterCall(parameter.class_index, offset));
@@ -216,35 +244,56 @@ impl SystemV {
     }
   }
 
-  pub fn trnslate_caller_argument(&self, index: usize, operations: &mut Program) {
-    let Some(parameter) = self.parameters.get(index) else {
-      panic!("function only has '{}' parameters, but tried to access the '{}'th parameter", self.parameters.len(), index)
-    };
-    match parameter.class {
-      ParameterClass::Integer(_) => {
-        operations.push(Operation::SysVIntegerArguemtnPreparation(parameter.class_index));
+  // All arguments are already on the stack, the first one deepest. Loading the registers only
+  // now keeps nested calls and 'div' (which uses rdx) from overwriting arguments that are
+  // already in place. The memory class arguments are copied below the others in reverse, so the
+  // first one ends up at the lowest address. Returns the bytes the caller has to drop afterwards.
+  pub fn translate_caller_arguments(&self, operations: &mut Program) -> usize {
+    let count = self.parameters.len();
+    let mut copies = 0;
+    for (index, parameter) in self.parameters.iter().enumerate().rev() {
+      if let ParameterClass::Memory(_) = parameter.class {
+        operations.push(Operation::SysVMemoryArgumentPreparation((count - 1 - index + copies) * 8));
+        copies += 1;
       }
-      ParameterClass::Sse(_) => {
-        operations.push(Operation::SysVSSEArgumentPreparation(parameter.class_index));
-      }
-      ParameterClass::Memory(offset) => {
-        operations.push(Operation::SysVMemoryArgumentPreparation(offset));
+    }
+    for (index, parameter) in self.parameters.iter().enumerate() {
+      let depth = (count - 1 - index + copies) * 8;
+      match parameter.class {
+        ParameterClass::Integer(_) => {
+          operations.push(Operation::SysVIntegerArguemtnPreparation(parameter.class_index, depth));
+        }
+        ParameterClass::Sse(_) => {
+          operations.push(Operation::SysVSSEArgumentPreparation(parameter.class_index, depth));
+        }
+        ParameterClass::Memory(_) => {}
       }
     }
+    (count + copies) * 8
   }
 
   pub fn translate_return(&self, program: &mut Program) {
     if let Some(ref return_type) = self.return_type {
       match return_type {
         PrimitiveTypes::Bool |
-        PrimitiveTypes::U64 => program.push(Operation::SysVIntegerReturn),
+        PrimitiveTypes::Str |
+        PrimitiveTypes::U64 |
+        PrimitiveTypes::U32 |
+        PrimitiveTypes::U16 |
+        PrimitiveTypes::U8 |
+        PrimitiveTypes::I64 |
+        PrimitiveTypes::I32 |
+        PrimitiveTypes::I16 |
+        PrimitiveTypes::I8 => program.push(Operation::SysVIntegerReturn),
         PrimitiveTypes::F64 => program.push(Operation::SysVSSEReturn),
         PrimitiveTypes::Void => {},
 
         PrimitiveTypes::Integer |
         PrimitiveTypes::Number |
         PrimitiveTypes::Float |
-        PrimitiveTypes::COUNT => todo!(),
+        PrimitiveTypes::Array(_, _) |
+        PrimitiveTypes::Struct(_, _) |
+        PrimitiveTypes::COUNT => unreachable!("the parser only allows single values as return types, but got '{}'", return_type),
       }
     }
     let name = self.func_name.deref().clone();
@@ -255,7 +304,15 @@ impl SystemV {
     if let Some(ref return_type) = self.return_type {
       match return_type {
         PrimitiveTypes::Bool |
-        PrimitiveTypes::U64 => {
+        PrimitiveTypes::Str |
+        PrimitiveTypes::U64 |
+        PrimitiveTypes::U32 |
+        PrimitiveTypes::U16 |
+        PrimitiveTypes::U8 |
+        PrimitiveTypes::I64 |
+        PrimitiveTypes::I32 |
+        PrimitiveTypes::I16 |
+        PrimitiveTypes::I8 => {
           program.push(Operation::SysVPushIntegerReturn);
         }
         PrimitiveTypes::F64 => {
@@ -266,7 +323,9 @@ impl SystemV {
         PrimitiveTypes::Number |
         PrimitiveTypes::Float |
         PrimitiveTypes::Integer |
-        PrimitiveTypes::COUNT => panic!(),
+        PrimitiveTypes::Array(_, _) |
+        PrimitiveTypes::Struct(_, _) |
+        PrimitiveTypes::COUNT => unreachable!("the parser only allows single values as return types, but got '{}'", return_type),
       }
     }
   }
@@ -1,9 +1,10 @@
-use core::panic;
 use std::cmp::Ordering;
+use std::mem;
 
-use crate::token::{Keyword, LocToken, Operator, Precedences, Token, OPERATOR_PRECEDENCES};
+use crate::diagnostic::{Diagnostic, Span};
+use crate::token::{Associativity, FStringPart, Keyword, COMPARISON_PRECEDENCE, CONDITIONAL_PRECEDENCE, LocToken, Operator, Precedences, Token, NOT_PRECEDENCE, OPERATOR_MAP, OPERATOR_PRECEDENCES, UNARY_PRECEDENCE};
 use crate::lexer::Lexer;
-use crate::ast::{match_return_type, match_variable_type, ASTNode, ASTNodeType, ConstLiteral, PrimitiveTypes};
+use crate::ast::{match_return_type, match_variable_type, ASTNode, ASTNodeType, ConstLiteral, FormatSpec, Interpolation, PrimitiveTypes};
 
 pub struct Parser<'a> {
     lexer: Lexer<'a>,
@@ -13,6 +14,8 @@ pub struct Parser<'a> {
     next_loc_token: LocToken,
     indent_stack: Vec<usize>,
     file_name: String,
+    diagnostics: Vec<Diagnostic>,
+    classes: Vec<String>, // names of the classes defined so far, they can be used as types
     // multi_line: bool,
 }
 
@@ -20,65 +23,105 @@ impl<'a> Parser<'a> {
 
     // PUBLIC
     pub fn new(lexer: Lexer<'a>, file_name: String) -> Self {
-        let mut parser = Parser {
+        Parser {
             lexer,
             current_token: Token::EOF,
-            current_loc_token: ((0,0), Token::EOF),
+            current_loc_token: (Span::at((0,0)), Token::EOF),
             next_token: Token::EOF,
-            next_loc_token: ((0,0), Token::EOF),
+            next_loc_token: (Span::at((0,0)), Token::EOF),
             indent_stack: Vec::new(),
             file_name,
+            diagnostics: Vec::new(),
+            classes: Vec::new(),
             // multi_line: false,
-        };
-        parser.advance(); // Load the first token
-        parser.advance(); // Load the second token
-        parser
+        }
     }
 
-    fn panic_loc<T>(&self, msg: &str) -> T {
-        let mut fmt: String = String::new();
-        let ((row, col), _) = &self.current_loc_token;
-        fmt.push_str(&self.file_name);
-        fmt.push(':');
-        fmt.push_str(&row.to_string());
-        fmt.push(':');
-        fmt.push_str(&col.to_string());
-        fmt.push('\n');
-        eprintln!("{}", fmt);
-        panic!("{}", msg);
+    fn error(&self, msg: impl Into<String>) -> Diagnostic {
+        let (span, _) = &self.current_loc_token;
+        Diagnostic::error(&self.file_name, *span, msg)
     }
 
     fn get_current_loc(&self) -> (usize, usize) {
-        self.current_loc_token.0
+        self.current_loc_token.0.loc
+    }
+
+    // Only the first error of a line is kept. Everything after it is most likely a follow-up error.
+    fn report(&mut self, diagnostic: Diagnostic) {
+        if let Some(last) = self.diagnostics.last() {
+            if last.span.loc.0 == diagnostic.span.loc.0 {
+                return;
+            }
+        }
+        self.diagnostics.push(diagnostic);
     }
 
     fn advance(&mut self) {
         self.current_loc_token = self.next_loc_token.clone();
-        self.next_loc_token = self.lexer.next_token();
+        self.next_loc_token = loop {
+            match self.lexer.next_token() {
+                Ok(loc_token) => break loc_token,
+                Err(diagnostic) => self.report(diagnostic),
+            }
+        };
         let (_, t) = &self.current_loc_token;
         self.current_token = t.clone();
         let (_, t) = &self.next_loc_token;
         self.next_token = t.clone();
     }
 
-    fn increse_indention(&mut self) {
+    fn skip_line(&mut self) {
+        while self.current_token != Token::Newline && self.current_token != Token::EOF {
+            self.advance();
+        }
+    }
+
+    // Recovers from a syntax error by skipping the rest of the line.
+    // Lines that are indented deeper than the current block are skipped as well,
+    // so a broken 'if', 'while' or 'def' does not report every line of its body.
+    fn synchronize(&mut self) {
+        self.skip_line();
+        let level = self.indent_stack.last().copied().unwrap_or(0);
+        loop {
+            match self.current_token {
+                Token::Newline => self.advance(),
+                Token::Indent(indent) if indent > level => self.skip_line(),
+                _ => break,
+            }
+        }
+    }
+
+    fn increse_indention(&mut self) -> Result<(), Diagnostic> {
         let Token::Indent(indent) = self.current_token else {
-            self.panic_loc("exprected indentinon!")
+            return Err(self.error("exprected indentinon!"))
         };
         if self.indent_stack.is_empty() {
             self.indent_stack.push(indent);
-            return;
+            return Ok(());
         }
         let &last = self.indent_stack.last().expect("safe");
         if last < indent {
             self.indent_stack.push(indent);
+            Ok(())
         }
         else {
-            self.panic_loc("Indention must be incresed!")
+            Err(self.error("Indention must be incresed!"))
         }
     }
 
-    pub fn parse(&mut self) -> Vec<ASTNode> {
+    pub fn parse(&mut self) -> Result<Vec<ASTNode>, Vec<Diagnostic>> {
+        self.advance(); // Load the first token
+        self.advance(); // Load the second token
+        let nodes = self.parse_block();
+        if self.diagnostics.is_empty() {
+            Ok(nodes)
+        }
+        else {
+            Err(mem::take(&mut self.diagnostics))
+        }
+    }
+
+    fn parse_block(&mut self) -> Vec<ASTNode> {
         let mut nodes = Vec::new();
 
         while self.current_token != Token::EOF {
@@ -98,165 +141,208 @@ impl<'a> Parser<'a> {
                             return nodes;
                         }
                         Ordering::Greater => {
-                            self.panic_loc("Unexpeted indention!")
+                            self.report(self.error("Unexpeted indention!"));
+                            self.synchronize();
+                            continue;
                         }
                     }
                 }
                 else {
-                    self.indent_stack.clear();
+                    // back at column 1. Every enclosing block pops its own level.
+                    self.indent_stack.pop();
                     return nodes;
                 }
             }
             else if let Token::Indent(_) = self.current_token {
-                self.panic_loc("Unexpeted indention!")
+                self.report(self.error("Unexpeted indention!"));
+                self.synchronize();
+                continue;
+            }
+            let loc = self.get_current_loc();
+            match self.parse_statement() {
+                Ok(node) => nodes.push(node),
+                Err(diagnostic) => {
+                    self.report(diagnostic);
+                    self.synchronize();
+                    nodes.push(ASTNode {
+                        node_type: ASTNodeType::Error,
+                        loc,
+                    });
+                }
             }
-            let node = self.parse_statement();
-            nodes.push(node);
         }
 
         nodes
     }
 
-    fn parse_statement(&mut self) -> ASTNode {
+    fn parse_statement(&mut self) -> Result<ASTNode, Diagnostic> {
         while self.current_token == Token::Newline {
             self.advance();
         }
+        let is_block = matches!(self.current_token, Token::Keyword(Keyword::Def | Keyword::If | Keyword::While | Keyword::For | Keyword::Const | Keyword::Class));
         let res: ASTNode = match &self.current_token {
             // empty line
             Token::Keyword(keyword) => match keyword {
                 Keyword::Def => {
-                    self.parse_function_def()
+                    self.parse_function_def()?
                 }
                 Keyword::Var => {
-                    self.parse_declaration()
+                    self.parse_declaration()?
                 }
                 Keyword::Const => {
-                    self.parse_const()
+                    self.parse_const()?
+                }
+                Keyword::Class => {
+                    self.parse_class()?
                 }
                 Keyword::If => {
-                    self.parse_if_else()
+                    self.parse_if_else()?
                 }
                 Keyword::While => {
-                    self.parse_while()
+                    self.parse_while()?
+                }
+                Keyword::For => {
+                    self.parse_for()?
                 }
                 Keyword::True => {
-                    self.parse_expression(Precedences::P0)
+                    self.parse_expression(Precedences::P0)?
                 }
                 Keyword::False => {
-                    self.parse_expression(Precedences::P0)
+                    self.parse_expression(Precedences::P0)?
+                }
+                Keyword::Not => {
+                    self.parse_statement_expression()?
                 }
                 Keyword::Return => {
-                    self.parse_return()
+                    self.parse_return()?
+                }
+                Keyword::Break | Keyword::Continue => {
+                    self.parse_loop_control()?
                 }
                 Keyword::Else => {
-                    self.panic_loc("Unexpected 'else' keyword.")
+                    return Err(self.error("Unexpected 'else' keyword."))
+                }
+                Keyword::Elif => {
+                    return Err(self.error("Unexpected 'elif' keyword."))
+                }
+                Keyword::In => {
+                    return Err(self.error("Unexpected 'in' keyword."))
                 }
             }
             Token::Identifier(_) => {
-                if let Token::Operator(Operator::Assignment, _) = self.next_token {
-                    self.parse_assignment()
+                if let Token::Operator(Operator::Assignment | Operator::AugmentedAssignment, _) = self.next_token {
+                    self.parse_assignment()?
+                }
+                else if Token::LBracket == self.next_token || Token::Dot == self.next_token {
+                    self.parse_assignment()?
+                }
+                else if Token::Colon == self.next_token {
+                    self.parse_declaration()?
                 }
                 else {
-                    self.parse_statement_expression()
+                    self.parse_statement_expression()?
                 }
             },
-            Token::Builtin(_) => self.parse_builtin(),
-            _ => self.parse_statement_expression()
+            Token::Builtin(name) if name == "len" => self.parse_statement_expression()?,
+            Token::Builtin(_) => self.parse_builtin()?,
+            _ => self.parse_statement_expression()?
         };
+        if !is_block && self.current_token != Token::Newline && self.current_token != Token::EOF {
+            return Err(self.error(format!("Expected newline '\\n' after statement, but got {}", self.current_token)));
+        }
         if self.current_token == Token::Newline {
             self.advance();
         }
-        res
+        Ok(res)
     }
 
-    fn parse_function_def(&mut self) -> ASTNode {
+    fn parse_function_def(&mut self) -> Result<ASTNode, Diagnostic> {
         if Token::Keyword(Keyword::Def) != self.current_token{
-            self.panic_loc("expected def keyword while parsing function definition.")
+            return Err(self.error("expected def keyword while parsing function definition."))
         }
         let loc = self.get_current_loc();
         self.advance(); // consume 'def'
 
         let Token::Identifier(name) = &self.current_token  else {
-            self.panic_loc("Expected function name after 'def'")
+            return Err(self.error("Expected function name after 'def'"))
         };
         let func_name = name.clone();
         self.advance(); // consume function name
 
         if Token::LParen != self.current_token{
-            self.panic_loc("expected '(' afer function name while parsing function definition.")
+            return Err(self.error("expected '(' afer function name while parsing function definition."))
         }
         self.advance(); // consume '('
 
         let mut args: Option<Vec<(String, PrimitiveTypes)>> = None;
         if Token::RParen != self.current_token {
-            args = Some(self.parse_function_def_args());
+            args = Some(self.parse_function_def_args()?);
         }
         self.advance(); // consume ')'
 
         let Token::Operator(Operator::ThinArrow, _) = self.current_token.clone() else {
-            self.panic_loc(&format!("Expected '->' during funciton definition, but got {:?}", self.current_token))
+            return Err(self.error(format!("Expected '->' during function definition, but got {}", self.current_token)))
         };
         self.advance(); // consume '->'
         let Token::Identifier(type_str) = self.current_token.clone() else {
-            self.panic_loc("Expected a type after '->' during function defenition")
+            return Err(self.error("Expected a type after '->' during function defenition"))
         };
         let Some(found_type) = match_return_type(&type_str) else {
-            self.panic_loc(&format!("'{}' is not a valid type", type_str))
+            return Err(self.error(format!("'{}' is not a valid type", type_str)))
         };
         let return_type = Some(found_type);
         self.advance(); // consume type
 
         if Token::Colon != self.current_token{
-            self.panic_loc("expected ':' while parsing function definition.")
+            return Err(self.error("expected ':' while parsing function definition."))
         }
         self.advance(); // consume ':'
         if Token::Newline != self.current_token{
-            self.panic_loc("expected newline '\\n' while parsing function definition.")
+            return Err(self.error("expected newline '\\n' while parsing function definition."))
         }
         self.advance(); // consume '\n'
 
         if !self.indent_stack.is_empty() {
-            self.panic_loc("functions can only be declared in the global scope.")
+            return Err(self.error("functions can only be declared in the global scope."))
         }
 
-        self.increse_indention();
-        let body = self.parse();
+        self.increse_indention()?;
+        let body = self.parse_block();
 
-        ASTNode {
+        Ok(ASTNode {
             node_type: ASTNodeType::FunctionDef(func_name, args, return_type, body,),
              loc,
-        }
+        })
 
     }
 
-    fn parse_function_def_args(&mut self) -> Vec<(String, PrimitiveTypes)> {
+    fn parse_function_def_args(&mut self) -> Result<Vec<(String, PrimitiveTypes)>, Diagnostic> {
         let mut args: Vec<(String, PrimitiveTypes)> = Vec::new();
         while self.current_token != Token::RParen {
             if self.current_token == Token::Newline {
-                self.panic_loc("Newlines '\\n' are currenlty not allowed during the definition of function parameters")
+                return Err(self.error("Newlines '\\n' are currenlty not allowed during the definition of function parameters"))
             }
             if self.current_token == Token::EOF {
-                self.panic_loc("Unexpected EOF while parsing function parameters")
+                return Err(self.error("Unexpected EOF while parsing function parameters"))
             }
 
             let Token::Identifier(name) = self.current_token.clone() else {
-                self.panic_loc("Expected identifier, got unexpected token at definition of funciton parameters")
+                return Err(self.error("Expected identifier, got unexpected token at definition of function parameters"))
             };
             self.advance();
 
             let Token::Colon = self.current_token.clone() else {
-                self.panic_loc("Expected ':', got unexpected token at definition of funciton parameters")
+                return Err(self.error("Expected ':', got unexpected token at definition of function parameters"))
             };
             self.advance();
 
             let Token::Identifier(type_str) = self.current_token.clone() else {
-                self.panic_loc("Expected type identifier, got unexpected token at definition of funciton parameters")
+                return Err(self.error("Expected type identifier, got unexpected token at definition of function parameters"))
             };
-            self.advance();
-
             let Some(typ) = match_variable_type(type_str.as_str()) else {
-                self.panic_loc("Uknown type while declaring function parameters")
+                return Err(self.error("Uknown type while declaring function parameters"))
             };
+            self.advance();
 
             args.push((name, typ));
 
@@ -265,27 +351,27 @@ impl<'a> Parser<'a> {
                     self.advance();
                 }
                 else {
-                    self.panic_loc("Expected identifier after ',' during function parameter definition")
+                    return Err(self.error("Expected identifier after ',' during function parameter definition"))
                 }
             }
         }
-        args
+        Ok(args)
     }
 
-    fn parse_function_call(&mut self) -> ASTNode {
+    fn parse_function_call(&mut self) -> Result<ASTNode, Diagnostic> {
         let Token::Identifier(name) = self.current_token.clone() else {
-            self.panic_loc("Expected identifier for function call.")
+            return Err(self.error("Expected identifier for function call."))
         };
         let loc = self.get_current_loc();
         self.advance();
         if Token::LParen != self.current_token{
-            self.panic_loc("expected '(' afer function name while parsing function call.")
+            return Err(self.error("expected '(' afer function name while parsing function call."))
         }
         self.advance(); // consume '('
 
         let mut args = Vec::new();
         if Token::RParen != self.current_token{
-            args = self.parse_function_call_args();
+            args = self.parse_function_call_args()?;
         }
         self.advance(); // consume ')'
 
@@ -293,337 +379,857 @@ impl<'a> Parser<'a> {
         //     // println!("WARNING: function calls can not be used as expressions at the moment");
         //     // println!("    Treated as a statement instead. This needs to be changed itf");
         //     if Token::Newline != self.current_token && Token::EOF != self.current_token{
-        //         self.panic_loc("expected newline '\\n' while parsing function call.")
+        //         return Err(self.error("expected newline '\\n' while parsing function call."))
         //     }
         // }
 
-        ASTNode {
+        Ok(ASTNode {
             node_type: ASTNodeType::FunctionCall(name.clone(), args, PrimitiveTypes::Void),
             loc
+        })
+    }
+
+    // Casts look like a call of the type: 'f64(x)'
+    fn parse_cast(&mut self) -> Result<ASTNode, Diagnostic> {
+        let loc = self.get_current_loc();
+        let Token::Identifier(name) = self.current_token.clone() else {
+            return Err(self.error("Expected a type for the cast."))
+        };
+        let Some(target) = match_variable_type(&name) else {
+            return Err(self.error(format!("Can not cast to unknown type '{}'", name)))
+        };
+        let ASTNodeType::FunctionCall(_, mut args, _) = self.parse_function_call()?.node_type else {
+            return Err(self.error("Expected '(' after the type of the cast."))
+        };
+        if args.len() != 1 {
+            return Err(Diagnostic::error(&self.file_name, Span::at(loc), format!("A cast to '{}' takes exactly one value, but got {}", name, args.len())))
         }
+        Ok(ASTNode {
+            node_type: ASTNodeType::Cast(Box::new(args.remove(0)), target),
+            loc,
+        })
     }
 
-    fn parse_function_call_args(&mut self) -> Vec<ASTNode> {
+    fn parse_function_call_args(&mut self) -> Result<Vec<ASTNode>, Diagnostic> {
         let mut args = Vec::new();
         while self.current_token != Token::RParen {
             if self.current_token == Token::Newline {
-                self.panic_loc("Newlines '\\n' are currenlty not allowed for parameters of a function call")
+                return Err(self.error("Newlines '\\n' are currenlty not allowed for parameters of a function call"))
             }
             if self.current_token == Token::EOF {
-                self.panic_loc("Unexpected EOF while parsing function call parameters")
+                return Err(self.error("Unexpected EOF while parsing function call parameters"))
             }
 
-            let expr = self.parse_expression(Precedences::P0);
+            let expr = self.parse_expression(Precedences::P0)?;
             args.push(expr);
 
             if self.current_token == Token::Comma {
                 if self.next_token == Token::RParen {
-                    self.panic_loc("Expected an other expression after ',' during function call parameters")
+                    return Err(self.error("Expected an other expression after ',' during function call parameters"))
                 }
                 self.advance();
             }
         }
-        args
+        Ok(args)
     }
 
-    fn parse_declaration(&mut self) -> ASTNode {
+    // 'var x: TYPE = expr', 'var x = expr' or without 'var' like Python: 'x: TYPE = expr'
+    fn parse_declaration(&mut self) -> Result<ASTNode, Diagnostic> {
         let loc = self.get_current_loc();
-        if Token::Keyword(Keyword::Var) != self.current_token {
-            self.panic_loc("Expected 'var' Keyword for declaration.")
+        if Token::Keyword(Keyword::Var) == self.current_token {
+            self.advance();
         }
-        self.advance();
 
         let Token::Identifier(name) = self.current_token.clone() else {
-            self.panic_loc("Expected an identifier for declaration.")
+            return Err(self.error("Expected an identifier for declaration."))
         };
         self.advance();
 
+        // 'var x = expr' leaves the type to the type checker
+        if let Token::Operator(Operator::Assignment, _) = self.current_token {
+            self.advance();
+            let expr = self.parse_expression(Precedences::P0)?;
+            return Ok(ASTNode {
+                node_type: ASTNodeType::Declaration(name, PrimitiveTypes::Void, Some(Box::new(expr))),
+                loc,
+            })
+        }
+
         let Token::Colon = self.current_token.clone() else {
-            self.panic_loc("Expected ':' after identifier for declaration.")
+            return Err(self.error("Expected ':' or '=' after identifier for declaration."))
         };
         self.advance();
 
         let Token::Identifier(typ_str) = self.current_token.clone() else {
-            self.panic_loc("Expected type (identefier) after identifier for declaration.")
+            return Err(self.error("Expected type (identefier) after identifier for declaration."))
         };
-        self.advance();
-
-        let Some(typ) = match_variable_type(&typ_str) else {
-            self.panic_loc(format!("Type with name '{}' does not exist", typ_str).as_str())
+        let Some(typ) = self.match_type(&typ_str) else {
+            return Err(self.error(format!("Type with name '{}' does not exist", typ_str)))
         };
+        self.advance();
+        let typ = self.parse_array_type(typ)?;
 
         if let Token::Operator(Operator::Assignment, _) = self.current_token {
             self.advance();
-            let expr = self.parse_expression(Precedences::P0);
-            ASTNode {
+            let expr = self.parse_expression(Precedences::P0)?;
+            Ok(ASTNode {
                 node_type: ASTNodeType::Declaration(name, typ, Some(Box::new(expr))),
                 loc,
-            }
+            })
         }
         else if self.current_token == Token::Newline {
-            ASTNode {
+            Ok(ASTNode {
                 node_type: ASTNodeType::Declaration(name, typ, None),
                 loc,
-            }
+            })
         }
         else {
-            self.panic_loc("Unexpeted Token after declaration.")
+            Err(self.error("Unexpeted Token after declaration."))
+        }
+    }
+
+    // the builtin types and the classes defined so far
+    fn match_type(&self, name: &str) -> Option<PrimitiveTypes> {
+        if self.classes.iter().any(|class| class == name) {
+            return Some(PrimitiveTypes::Struct(name.to_string(), 0))
+        }
+        match_variable_type(name)
+    }
+
+    // 'u64[16]' is an array of 16 u64
+    fn parse_array_type(&mut self, element: PrimitiveTypes) -> Result<PrimitiveTypes, Diagnostic> {
+        if self.current_token != Token::LBracket {
+            return Ok(element)
+        }
+        self.advance(); // consume '['
+        let Token::Integer(len) = self.current_token.clone() else {
+            return Err(self.error("Expected the number of elements of the array."))
+        };
+        let len = match len.parse::<usize>() {
+            Ok(len) if len > 0 => len,
+            _ => return Err(self.error(format!("An array needs a size of at least one element, but got '{}'", len))),
+        };
+        self.advance();
+        if self.current_token != Token::RBracket {
+            return Err(self.error("Expected ']' after the size of the array."))
+        }
+        self.advance(); // consume ']'
+        Ok(PrimitiveTypes::Array(Box::new(element), len))
+    }
+
+    fn parse_index(&mut self) -> Result<ASTNode, Diagnostic> {
+        let loc = self.get_current_loc();
+        let Token::Identifier(name) = self.current_token.clone() else {
+            return Err(self.error("Expected the name of an array."))
+        };
+        self.advance(); // consume name
+        if self.current_token != Token::LBracket {
+            return Err(self.error("Expected '[' after the name of the array."))
+        }
+        self.advance(); // consume '['
+        let index = self.parse_expression(Precedences::P0)?;
+        if self.current_token != Token::RBracket {
+            return Err(self.error("Expected ']' after the index."))
+        }
+        self.advance(); // consume ']'
+        let array = ASTNode { node_type: ASTNodeType::Identifier(name, PrimitiveTypes::Void), loc };
+        Ok(ASTNode {
+            node_type: ASTNodeType::Index(Box::new(array), Box::new(index), PrimitiveTypes::Void),
+            loc,
+        })
+    }
+
+    // 'p.x', the field 'x' of the instance 'p'
+    fn parse_field(&mut self) -> Result<ASTNode, Diagnostic> {
+        let loc = self.get_current_loc();
+        let Token::Identifier(name) = self.current_token.clone() else {
+            return Err(self.error("Expected the name of an instance of a class."))
+        };
+        self.advance(); // consume name
+        if self.current_token != Token::Dot {
+            return Err(self.error("Expected '.' after the name of the instance."))
+        }
+        self.advance(); // consume '.'
+        let Token::Identifier(field) = self.current_token.clone() else {
+            return Err(self.error("Expected the name of a field after '.'"))
+        };
+        self.advance(); // consume field
+        let instance = ASTNode { node_type: ASTNodeType::Identifier(name, PrimitiveTypes::Void), loc };
+        Ok(ASTNode {
+            node_type: ASTNodeType::Field(Box::new(instance), field, PrimitiveTypes::Void, 0),
+            loc,
+        })
+    }
+
+    // '[1, 2, 3]', the elements of an array
+    fn parse_list(&mut self) -> Result<ASTNode, Diagnostic> {
+        let loc = self.get_current_loc();
+        if self.current_token != Token::LBracket {
+            return Err(self.error("Expected '[' at the start of a list."))
+        }
+        self.advance(); // consume '['
+        let mut elements = Vec::new();
+        while self.current_token != Token::RBracket {
+            if matches!(self.current_token, Token::Newline | Token::EOF) {
+                return Err(self.error("Expected ']' at the end of the list."))
+            }
+            elements.push(self.parse_expression(Precedences::P0)?);
+            match self.current_token {
+                Token::Comma => self.advance(),
+                Token::RBracket => {}
+                _ => return Err(self.error("Expected ',' or ']' after an element of the list.")),
+            }
         }
+        self.advance(); // consume ']'
+        if elements.is_empty() {
+            return Err(Diagnostic::error(&self.file_name, Span::at(loc), "A list needs at least one element, arrays can not be empty"))
+        }
+        Ok(ASTNode {
+            node_type: ASTNodeType::List(elements, PrimitiveTypes::Void),
+            loc,
+        })
     }
 
-    fn parse_assignment(&mut self) -> ASTNode {
+    fn parse_assignment(&mut self) -> Result<ASTNode, Diagnostic> {
         let loc = self.get_current_loc();
         let Token::Identifier(var_name) = &self.current_token else {
-            self.panic_loc("Expected identifier for assignment")
+            return Err(self.error("Expected identifier for assignment"))
         };
         let name = var_name.clone();
-        self.advance(); // consume variable name
+        // 'a[i] = value' assigns to an element, 'p.x = value' to a field
+        let target = if Token::LBracket == self.next_token {
+            self.parse_index()?
+        } else if Token::Dot == self.next_token {
+            self.parse_field()?
+        } else {
+            self.advance(); // consume variable name
+            ASTNode { node_type: ASTNodeType::Identifier(name.clone(), PrimitiveTypes::Void), loc }
+        };
 
-        let Token::Operator(Operator::Assignment, _) = self.current_token else {
-            self.panic_loc(format!("Expected '=' after identifier: {:#?}", self.current_loc_token).as_str())
+        let Token::Operator(op @ (Operator::Assignment | Operator::AugmentedAssignment), symbol) = self.current_token.clone() else {
+            let target = match target.node_type {
+                ASTNodeType::Index(_, _, _) => format!("{name}[...]"),
+                ASTNodeType::Field(_, ref field, _, _) => format!("{name}.{field}"),
+                _ => name,
+            };
+            return Err(self.error(format!("Expected '=' after '{}', got {}", target, self.current_token)))
         };
+        let op_loc = self.get_current_loc();
         self.advance(); // consume '='
 
-        let value = self.parse_expression(Precedences::P0);
-        ASTNode {
-            node_type: ASTNodeType::Assignment(name, Box::new(value)),
-            loc,
+        let mut value = self.parse_expression(Precedences::P0)?;
+        // 'x += 1' is 'x = x + 1'
+        if op == Operator::AugmentedAssignment {
+            // 'a[i] += 1' evaluates 'i' twice, a call in it would run twice
+            if let ASTNodeType::Index(_, ref index, _) = target.node_type {
+                if index.has_call() {
+                    return Err(Diagnostic::error(&self.file_name, Span::at(index.loc), format!("The index of '{}[...] {}' can not contain a call. Assign it to a variable first", name, symbol)))
+                }
+            }
+            let Some(op_type) = OPERATOR_MAP.get(symbol.trim_end_matches('=')) else {
+                return Err(Diagnostic::error(&self.file_name, Span::at(op_loc), format!("Unknown operator in '{}'", symbol)))
+            };
+            value = ASTNode {
+                node_type: ASTNodeType::BinaryOp(
+                    Box::new(target.clone()),
+                    op_type.clone(),
+                    Box::new(value),
+                    PrimitiveTypes::Void,
+                ),
+                loc: op_loc,
+            };
         }
+        let node_type = match target.node_type {
+            ASTNodeType::Index(array, index, _) => ASTNodeType::IndexAssignment(array, index, Box::new(value)),
+            ASTNodeType::Field(_, _, _, _) => ASTNodeType::FieldAssignment(Box::new(target), Box::new(value)),
+            _ => ASTNodeType::Assignment(name, Box::new(value)),
+        };
+        Ok(ASTNode {
+            node_type,
+            loc,
+        })
     }
 
-    fn parse_operant(&mut self) -> ASTNode {
+    fn parse_operant(&mut self) -> Result<ASTNode, Diagnostic> {
         let loc = self.get_current_loc();
         match &self.current_token {
             Token::LParen => {
                 self.advance();
-                let expr = self.parse_expression(Precedences::P0);
+                let expr = self.parse_expression(Precedences::P0)?;
                 if self.current_token != Token::RParen {
-                    self.panic_loc("Expecred a ')' here!")
+                    return Err(self.error("Expecred a ')' here!"))
                 }
                 self.advance();
-                expr
+                Ok(expr)
             }
             Token::RParen => {
-                self.panic_loc("Did not exprect ')' here!")
+                Err(self.error("Did not exprect ')' here!"))
+            }
+            Token::Operator(op @ (Operator::Minus | Operator::BitNot), _) => {
+                let op = op.clone();
+                self.advance();
+                let operand = self.parse_expression(UNARY_PRECEDENCE)?;
+                // '-5' is a literal of its own, so it can be range checked as a whole (e.g. -128 for i8)
+                if let (Operator::Minus, ASTNodeType::Literal(ref typ, ref value)) = (&op, &operand.node_type) {
+                    if !value.starts_with('-') && !matches!(typ, PrimitiveTypes::Bool | PrimitiveTypes::Str) {
+                        return Ok(ASTNode {
+                            node_type: ASTNodeType::Literal(typ.clone(), format!("-{value}")),
+                            loc,
+                        })
+                    }
+                }
+                Ok(ASTNode {
+                    node_type: ASTNodeType::UnaryOp(op, Box::new(operand), PrimitiveTypes::Void),
+                    loc,
+                })
             }
             Token::Identifier(s) => {
-                if Token::LParen == self.next_token {
+                if Token::LParen == self.next_token && match_variable_type(s).is_some() {
+                    self.parse_cast()
+                }
+                else if Token::LBracket == self.next_token {
+                    self.parse_index()
+                }
+                else if Token::Dot == self.next_token {
+                    self.parse_field()
+                }
+                else if Token::LParen == self.next_token {
                     self.parse_function_call()
                 }
                 else {
                     let _s = s.clone();
                     self.advance();
-                    ASTNode {
+                    Ok(ASTNode {
                         node_type: ASTNodeType::Identifier(_s, PrimitiveTypes::Void),
                         loc,
-                    }
+                    })
                 }
             }
             Token::Float(n) => {
                 let _n = n.clone();
                 self.advance();
-                ASTNode {
+                Ok(ASTNode {
                     node_type: ASTNodeType::Literal(PrimitiveTypes::Float, _n),
                     loc,
+                })
+            }
+            Token::String(value) => {
+                let value = value.clone();
+                self.advance();
+                Ok(ASTNode {
+                    node_type: ASTNodeType::Literal(PrimitiveTypes::Str, value),
+                    loc,
+                })
+            }
+            Token::FString(parts) => {
+                let parts = parts.clone();
+                self.advance();
+                let mut interpolations = Vec::new();
+                for part in parts {
+                    interpolations.push(match part {
+                        FStringPart::Text(text) => Interpolation::Text(text),
+                        FStringPart::Expression(source, spec, loc) => {
+                            let value = self.parse_interpolation(&source, loc)?;
+                            let spec = match spec {
+                                Some(spec) => Some(self.parse_format_spec(&spec, loc)?),
+                                None => None,
+                            };
+                            Interpolation::Value(value, spec)
+                        }
+                    });
                 }
+                Ok(ASTNode {
+                    node_type: ASTNodeType::FString(interpolations),
+                    loc,
+                })
             }
+            Token::Builtin(name) if name == "len" => self.parse_len(),
+            Token::LBracket => self.parse_list(),
             Token::Keyword(Keyword::True) => {
                 self.advance();
-                ASTNode {
+                Ok(ASTNode {
                     node_type: ASTNodeType::Literal(PrimitiveTypes::Bool, "true".to_string()),
                     loc,
-                }
+                })
             }
             Token::Keyword(Keyword::False) => {
                 self.advance();
-                ASTNode {
+                Ok(ASTNode {
                     node_type: ASTNodeType::Literal(PrimitiveTypes::Bool, "false".to_string()),
                     loc,
-                }
+                })
             }
             Token::Integer(n) => {
                 let _n = n.clone();
                 self.advance();
-                ASTNode {
+                Ok(ASTNode {
                     node_type: ASTNodeType::Literal(PrimitiveTypes::Number, _n),
                     loc,
-                }
+                })
             }
             _ => {
-                self.panic_loc(format!("Unexoected Token here: {:#?}", self.current_token).as_str())
+                Err(self.error(format!("Unexpected token here: {}", self.current_token)))
             }
         }
     }
 
-    fn parse_expression(&mut self, prec: Precedences) -> ASTNode {
+    fn parse_expression(&mut self, prec: Precedences) -> Result<ASTNode, Diagnostic> {
         if prec >= Precedences::Count {
             return self.parse_operant();
         }
 
-        let lhs = self.parse_expression(prec.increment());
-        if self.current_token != Token::EOF && self.current_token != Token::Newline && self.current_token != Token::Colon {
-            if let Token::Operator(op_type, ch) = self.current_token.clone() {
-                let loc = self.get_current_loc();
-                if OPERATOR_PRECEDENCES.get(&ch).expect("Wiil always be safe") == &prec {
-                    self.advance();
-                    let rhs = self.parse_expression(prec);
+        if prec == NOT_PRECEDENCE && self.current_token == Token::Keyword(Keyword::Not) {
+            let loc = self.get_current_loc();
+            self.advance();
+            let operand = self.parse_expression(prec)?;
+            return Ok(ASTNode {
+                node_type: ASTNodeType::UnaryOp(Operator::Not, Box::new(operand), PrimitiveTypes::Void),
+                loc,
+            });
+        }
 
-                    return ASTNode {
-                        node_type: ASTNodeType::BinaryOp(Box::new(lhs), op_type.clone(), Box::new(rhs), PrimitiveTypes::Void),
-                        loc,
-                    };
-                }
+        let mut lhs = self.parse_expression(prec.increment())?;
+        // only comparisons built by this loop chain, not parenthesized ones
+        let mut lhs_is_comparison = false;
+        while let Token::Operator(op_type, ch) = self.current_token.clone() {
+            let Some((op_prec, associativity)) = OPERATOR_PRECEDENCES.get(&ch) else {
+                break; // unary only operator
+            };
+            if op_prec != &prec {
+                break;
+            }
+            let loc = self.get_current_loc();
+            self.advance();
+
+            // 'is not' is one operator
+            let op_type = if op_type == Operator::Is && self.current_token == Token::Keyword(Keyword::Not) {
+                self.advance();
+                Operator::IsNot
+            } else {
+                op_type
+            };
+
+            // A left associative operator only takes the next operand of a higher precedence,
+            // so the loop folds 'a - b - c' into '(a - b) - c'.
+            let rhs = match associativity {
+                Associativity::Left => self.parse_expression(prec.increment())?,
+                Associativity::Right => self.parse_expression(prec.clone())?,
+            };
+            // 'a < b < c' is one chain, '(a < b) < c' compares a bool
+            if prec == COMPARISON_PRECEDENCE && op_type.is_comparison() && lhs_is_comparison {
+                lhs = match lhs.node_type {
+                    ASTNodeType::BinaryOp(left, first_op, right, _) => ASTNode {
+                        node_type: ASTNodeType::ChainedComparison(vec![*left, *right, rhs], vec![first_op, op_type]),
+                        loc: lhs.loc,
+                    },
+                    ASTNodeType::ChainedComparison(mut operands, mut ops) => {
+                        operands.push(rhs);
+                        ops.push(op_type);
+                        ASTNode { node_type: ASTNodeType::ChainedComparison(operands, ops), loc: lhs.loc }
+                    }
+                    _ => return Err(self.error("Expected a comparison to chain.")),
+                };
+                continue;
+            }
+            lhs_is_comparison = op_type.is_comparison();
+            lhs = ASTNode {
+                node_type: ASTNodeType::BinaryOp(Box::new(lhs), op_type, Box::new(rhs), PrimitiveTypes::Void),
+                loc,
+            };
+        }
+
+        // the else branch can be another conditional: 'a if x else b if y else c'
+        if prec == CONDITIONAL_PRECEDENCE && self.current_token == Token::Keyword(Keyword::If) {
+            let loc = self.get_current_loc();
+            self.advance();
+            let cond = self.parse_expression(prec.increment())?;
+            if self.current_token != Token::Keyword(Keyword::Else) {
+                return Err(self.error("Expected 'else' in the conditional expression."))
             }
+            self.advance();
+            let els = self.parse_expression(prec)?;
+            lhs = ASTNode {
+                node_type: ASTNodeType::Conditional(Box::new(cond), Box::new(lhs), Box::new(els), PrimitiveTypes::Void),
+                loc,
+            };
         }
-        lhs
+        Ok(lhs)
     }
 
-    fn parse_statement_expression(&mut self) -> ASTNode {
-        ASTNode {
-            node_type: ASTNodeType::SExpression(Box::new(self.parse_expression(Precedences::P0))),
+    fn parse_statement_expression(&mut self) -> Result<ASTNode, Diagnostic> {
+        Ok(ASTNode {
+            node_type: ASTNodeType::SExpression(Box::new(self.parse_expression(Precedences::P0)?)),
             loc: self.get_current_loc(),
-        }
+        })
     }
 
-    fn parse_builtin(&mut self) -> ASTNode {
+    fn parse_builtin(&mut self) -> Result<ASTNode, Diagnostic> {
         let Token::Builtin(name) = self.current_token.clone() else {
-            self.panic_loc(format!("Tried to parse builtin token but found this: {:#?}", self.current_token).as_str())
+            return Err(self.error(format!("Tried to parse builtin token but found {}", self.current_token)))
         };
         let loc = self.get_current_loc();
         self.advance();
         if self.current_token != Token::LParen {
-            self.panic_loc("expected '(' adter print.")
+            return Err(self.error(format!("expected '(' after {}.", name)))
+        }
+        self.advance(); // consume '('
+
+        let mut args = Vec::new();
+        if Token::RParen != self.current_token {
+            args = self.parse_function_call_args()?;
+        }
+        self.advance(); // consume ')'
+
+        // 'print' takes any number of values, the typed ones exactly one
+        if name != "print" && args.len() != 1 {
+            return Err(Diagnostic::error(&self.file_name, Span::at(loc), format!("'{}' takes exactly one value, but got {}", name, args.len())))
         }
-        let expr = self.parse_expression(Precedences::P0);
-        ASTNode {
-            node_type: ASTNodeType::BuiltinFunction(name, Box::new(expr)),
+        Ok(ASTNode {
+            node_type: ASTNodeType::BuiltinFunction(name, args),
             loc,
+        })
+    }
+
+    // The lexer keeps the expressions of an f-string as text. Each one gets a parser of its own.
+    fn parse_interpolation(&self, source: &str, loc: (usize, usize)) -> Result<ASTNode, Diagnostic> {
+        let lexer = Lexer::new(source, self.file_name.clone()).starting_at(loc);
+        let mut parser = Parser::new(lexer, self.file_name.clone());
+        parser.advance();
+        parser.advance();
+        let value = parser.parse_expression(Precedences::P0);
+        if let Some(diagnostic) = parser.diagnostics.first() {
+            return Err(diagnostic.clone())
         }
+        let value = value?;
+        if parser.current_token != Token::EOF {
+            return Err(parser.error("Expected '}' after the expression in the f-string."))
+        }
+        Ok(value)
+    }
+
+    // Only the precision of floats can be formatted: '.3' or '.3f'
+    fn parse_format_spec(&self, spec: &str, loc: (usize, usize)) -> Result<FormatSpec, Diagnostic> {
+        let error = || Diagnostic::error(&self.file_name, Span::at(loc), format!("Unsupported format spec '{}'. Expected '.N' or '.Nf'", spec));
+        let Some(digits) = spec.strip_prefix('.') else {
+            return Err(error())
+        };
+        let (digits, fixed) = match digits.strip_suffix('f') {
+            Some(digits) => (digits, true),
+            None => (digits, false),
+        };
+        let Ok(precision) = digits.parse::<usize>() else {
+            return Err(error())
+        };
+        if precision > 15 {
+            return Err(Diagnostic::error(&self.file_name, Span::at(loc), format!("At most 15 decimals can be printed, but got {}", precision)))
+        }
+        Ok(FormatSpec { precision, fixed })
+    }
+
+    // 'len(x)' is the only builtin that returns a value
+    fn parse_len(&mut self) -> Result<ASTNode, Diagnostic> {
+        let loc = self.get_current_loc();
+        let ASTNodeType::BuiltinFunction(name, mut args) = self.parse_builtin()?.node_type else {
+            return Err(self.error("Expected '(' after len."))
+        };
+        if args.len() != 1 {
+            return Err(Diagnostic::error(&self.file_name, Span::at(loc), format!("'{}' takes exactly one value, but got {}", name, args.len())))
+        }
+        Ok(ASTNode {
+            node_type: ASTNodeType::Len(Box::new(args.remove(0))),
+            loc,
+        })
     }
 
-    fn parse_if_else(&mut self) ->ASTNode {
+    fn parse_if_else(&mut self) -> Result<ASTNode, Diagnostic> {
         // I relized know that i loose the location of the else keyword...
         let loc = self.get_current_loc();
-        if Token::Keyword(Keyword::If) != self.current_token {
-            self.panic_loc("Expected if token.")
+        if Token::Keyword(Keyword::If) != self.current_token && Token::Keyword(Keyword::Elif) != self.current_token {
+            return Err(self.error("Expected 'if' or 'elif' token."))
         }
         self.advance();
 
-        let cond = self.parse_expression(Precedences::P0);
+        let cond = self.parse_expression(Precedences::P0)?;
         if Token::Colon != self.current_token {
-            self.panic_loc("Expected ':' after the expression of the if condition.")
+            return Err(self.error("Expected ':' after the expression of the if condition."))
         }
         self.advance();
 
         if Token::Newline != self.current_token {
-            self.panic_loc("Expected new line '\\n' after ':' for the if condition.")
+            return Err(self.error("Expected new line '\\n' after ':' for the if condition."))
         }
         self.advance();
 
-        self.increse_indention();
-        let then = self.parse();
+        self.increse_indention()?;
+        let then = self.parse_block();
+
+        // 'elif' is parsed as an 'if' that is the only statement of the else branch
+        if self.continues_with(Keyword::Elif) {
+            let elif = self.parse_if_else()?;
+            return Ok(ASTNode {
+                node_type: ASTNodeType::If(Box::new(cond), then, Some(vec![elif])),
+                loc,
+            })
+        }
 
-        if Token::Keyword(Keyword::Else) != self.current_token {
-            return ASTNode {
+        if !self.continues_with(Keyword::Else) {
+            return Ok(ASTNode {
                 node_type: ASTNodeType::If(Box::new(cond), then, None),
                 loc,
-            }
+            })
         }
         self.advance();
 
         if Token::Colon != self.current_token {
-            self.panic_loc("Expected ':' after else keyword.")
+            return Err(self.error("Expected ':' after else keyword."))
         }
         self.advance();
 
         if Token::Newline != self.current_token {
-            self.panic_loc("Expected newline '\\n' after ':' for else keyword.")
+            return Err(self.error("Expected newline '\\n' after ':' for else keyword."))
         }
         self.advance();
 
         // Indetion check
         if let Token::Indent(_) = self.current_token {
-            self.increse_indention();
+            self.increse_indention()?;
         }
 
-        let els = self.parse();
-        ASTNode {
+        let els = self.parse_block();
+        Ok(ASTNode {
             node_type: ASTNodeType::If(Box::new(cond), then, Some(els)),
             loc,
+        })
+    }
+
+    // After a block ended, the parser sits at the start of the next line.
+    // Checks if that line continues the current statement with the given keyword (e.g. 'else')
+    // and consumes its indention if so.
+    fn continues_with(&mut self, keyword: Keyword) -> bool {
+        let level = self.indent_stack.last().copied().unwrap_or(0);
+        match self.current_token {
+            Token::Indent(indent) if indent == level && self.next_token == Token::Keyword(keyword.clone()) => {
+                self.advance();
+                true
+            }
+            Token::Indent(_) => false,
+            _ => level == 0 && self.current_token == Token::Keyword(keyword),
         }
     }
 
-    fn parse_while(&mut self) -> ASTNode{
+    fn parse_while(&mut self) -> Result<ASTNode, Diagnostic> {
         let loc = self.get_current_loc();
         if Token::Keyword(Keyword::While) != self.current_token {
-            self.panic_loc("Expected 'while' token here.")
+            return Err(self.error("Expected 'while' token here."))
         }
         self.advance();
 
-        let cond = self.parse_expression(Precedences::P0);
+        let cond = self.parse_expression(Precedences::P0)?;
 
         if Token::Colon != self.current_token {
-            self.panic_loc("Expected ':' after while expression.")
+            return Err(self.error("Expected ':' after while expression."))
         }
         self.advance();
 
         if Token::Newline != self.current_token {
-            self.panic_loc("Expected newline '\\n' after : for while loop.")
+            return Err(self.error("Expected newline '\\n' after : for while loop."))
         }
         self.advance();
 
-        self.increse_indention();
-        let body = self.parse();
+        self.increse_indention()?;
+        let body = self.parse_block();
 
-        ASTNode {
+        Ok(ASTNode {
             node_type: ASTNodeType::While(Box::new(cond), body),
             loc,
+        })
+    }
+
+    // for NAME in range([start,] stop[, step]): or for NAME in ARRAY:
+    fn parse_for(&mut self) -> Result<ASTNode, Diagnostic> {
+        let loc = self.get_current_loc();
+        if Token::Keyword(Keyword::For) != self.current_token {
+            return Err(self.error("Expected 'for' token here."))
+        }
+        self.advance();
+
+        let Token::Identifier(name) = self.current_token.clone() else {
+            return Err(self.error("Expected the name of the loop variable after 'for'"))
+        };
+        self.advance();
+
+        if Token::Keyword(Keyword::In) != self.current_token {
+            return Err(self.error("Expected 'in' after the loop variable."))
         }
+        self.advance();
+
+        // 'for x in a:' goes over the elements of the array 'a'
+        if let Token::Identifier(array) = self.current_token.clone() {
+            if array != "range" {
+                let array = ASTNode { node_type: ASTNodeType::Identifier(array, PrimitiveTypes::Void), loc: self.get_current_loc() };
+                self.advance(); // consume the array
+                if Token::Colon != self.current_token {
+                    return Err(self.error("Expected ':' after the array of the for loop."))
+                }
+                self.advance();
+                if Token::Newline != self.current_token {
+                    return Err(self.error("Expected newline '\\n' after : for for loop."))
+                }
+                self.advance();
+
+                self.increse_indention()?;
+                let body = self.parse_block();
+                return Ok(ASTNode {
+                    node_type: ASTNodeType::ForEach(name, PrimitiveTypes::Void, Box::new(array), body),
+                    loc,
+                })
+            }
+        }
+
+        if Token::Identifier("range".to_string()) != self.current_token {
+            return Err(self.error("Expected 'range(...)' or an array after 'in'. Only ranges and arrays can be iterated over."))
+        }
+        let range_loc = self.get_current_loc();
+        let ASTNodeType::FunctionCall(_, args, _) = self.parse_function_call()?.node_type else {
+            return Err(self.error("Expected 'range(...)' after 'in'."))
+        };
+        let literal = |n: &str| ASTNode {
+            node_type: ASTNodeType::Literal(PrimitiveTypes::Number, n.to_string()),
+            loc: range_loc,
+        };
+        let mut args = args.into_iter();
+        let (start, stop, step) = match (args.next(), args.next(), args.next(), args.next()) {
+            (Some(stop), None, None, None) => (literal("0"), stop, literal("1")),
+            (Some(start), Some(stop), None, None) => (start, stop, literal("1")),
+            (Some(start), Some(stop), Some(step), None) => (start, stop, step),
+            _ => return Err(Diagnostic::error(&self.file_name, Span::at(range_loc), "range() expects 1 to 3 arguments")),
+        };
+
+        if Token::Colon != self.current_token {
+            return Err(self.error("Expected ':' after range(...) of the for loop."))
+        }
+        self.advance();
+
+        if Token::Newline != self.current_token {
+            return Err(self.error("Expected newline '\\n' after : for for loop."))
+        }
+        self.advance();
+
+        self.increse_indention()?;
+        let body = self.parse_block();
+
+        Ok(ASTNode {
+            node_type: ASTNodeType::For(name, PrimitiveTypes::Void, Box::new(start), Box::new(stop), Box::new(step), body),
+            loc,
+        })
+    }
+
+    // class NAME:
+    //     FIELD: TYPE
+    fn parse_class(&mut self) -> Result<ASTNode, Diagnostic> {
+        let loc = self.get_current_loc();
+        if Token::Keyword(Keyword::Class) != self.current_token {
+            return Err(self.error("Expected 'class' token here."))
+        }
+        self.advance();
+
+        let Token::Identifier(name) = self.current_token.clone() else {
+            return Err(self.error("Expected the name of the class after 'class'"))
+        };
+        if self.match_type(&name).is_some() {
+            return Err(self.error(format!("Type with name '{}' does already exist", name)))
+        }
+        self.advance();
+
+        if Token::Colon != self.current_token {
+            return Err(self.error("Expected ':' after the name of the class."))
+        }
+        self.advance();
+
+        if Token::Newline != self.current_token {
+            return Err(self.error("Expected newline '\\n' after : for class."))
+        }
+        self.advance();
+
+        if !self.indent_stack.is_empty() {
+            return Err(self.error("classes can only be declared in the global scope."))
+        }
+
+        // the fields are parsed like declarations without a value
+        self.increse_indention()?;
+        let mut fields = Vec::new();
+        for node in self.parse_block() {
+            match node.node_type {
+                ASTNodeType::Declaration(field, typ, None) => fields.push((field, typ, node.loc)),
+                ASTNodeType::Error => {}
+                _ => self.report(Diagnostic::error(&self.file_name, Span::at(node.loc), "A class can only contain fields like 'x: f64'")),
+            }
+        }
+        self.classes.push(name.clone());
+
+        Ok(ASTNode {
+            node_type: ASTNodeType::Class(name, fields),
+            loc,
+        })
     }
 
-    fn parse_return(&mut self) -> ASTNode {
+    fn parse_loop_control(&mut self) -> Result<ASTNode, Diagnostic> {
+        let loc = self.get_current_loc();
+        let node_type = match self.current_token {
+            Token::Keyword(Keyword::Break) => ASTNodeType::Break,
+            Token::Keyword(Keyword::Continue) => ASTNodeType::Continue,
+            _ => return Err(self.error("Expected 'break' or 'continue' here.")),
+        };
+        self.advance();
+        Ok(ASTNode {
+            node_type,
+            loc,
+        })
+    }
+
+    fn parse_return(&mut self) -> Result<ASTNode, Diagnostic> {
         if self.current_token != Token::Keyword(Keyword::Return) {
-            self.panic_loc("Expected 'return' here.")
+            return Err(self.error("Expected 'return' here."))
         }
         let loc = self.get_current_loc();
         self.advance();
 
         let mut expr = None;
-        if self.current_token != Token::Newline || self.current_token != Token::EOF {
-            expr = Some(Box::new(self.parse_expression(Precedences::P0)));
+        if self.current_token != Token::Newline && self.current_token != Token::EOF {
+            expr = Some(Box::new(self.parse_expression(Precedences::P0)?));
         }
-        ASTNode {
+        Ok(ASTNode {
             loc,
             node_type: ASTNodeType::Return(expr)
-        }
+        })
     }
 
-    fn parse_const(&mut self) -> ASTNode {
+    fn parse_const(&mut self) -> Result<ASTNode, Diagnostic> {
         let Token::Keyword(Keyword::Const) = self.current_token else {
-            self.panic_loc("Expected keyword 'const' here.")
+            return Err(self.error("Expected keyword 'const' here."))
         };
         self.advance(); // consume 'const'
 
         let Token::Identifier(identifier) = self.current_token.clone() else {
-            self.panic_loc("Expected identifier here.")
+            return Err(self.error("Expected identifier here."))
         };
         self.advance(); // consume identifier
 
         let Token::Colon = self.current_token else {
-            self.panic_loc(&format!("Expected ':' for type definition, but got {:?}", self.current_token))
+            return Err(self.error(format!("Expected ':' for type definition, but got {}", self.current_token)))
         };
         self.advance(); // consume ':'
 
         let Token::Identifier(type_str) = self.current_token.clone() else {
-            self.panic_loc(&format!("Expected type identifier while parsing a const definition, but got {:?}", self.current_token))
+            return Err(self.error(format!("Expected type identifier while parsing a const definition, but got {}", self.current_token)))
         };
-        self.advance(); // consume type identifier
         let Some(const_type) = match_return_type(&type_str) else {
-            self.panic_loc(&format!("Unknown or unsupported type {type_str}"))
+            return Err(self.error(format!("Unknown or unsupported type {type_str}")))
         };
+        self.advance(); // consume type identifier
 
         let Token::Operator(Operator::Assignment, _) = self.current_token else {
-            self.panic_loc("Const declared variables need to be initialized immediately.")
+            return Err(self.error("Const declared variables need to be initialized immediately."))
         };
         self.advance();
 
@@ -638,27 +1244,32 @@ impl<'a> Parser<'a> {
                 ConstLiteral::Float(value.clone())
             }
 
+            Token::String(_) |
+            Token::FString(_) |
             Token::Keyword(_) |
             Token::Builtin(_) |
             Token::Identifier(_) |
             Token::Operator(_, _) |
             Token::LParen |
             Token::RParen |
+            Token::LBracket |
+            Token::RBracket |
+            Token::Dot |
             Token::Comma |
             Token::Newline |
             Token::Indent(_) |
             Token::Colon |
-            Token::EOF => self.panic_loc(&format!("Only literals are allowed for a const assignment, but got {:?}", self.current_token)),
+            Token::EOF => return Err(self.error(format!("Only literals are allowed for a const assignment, but got {}", self.current_token))),
         };
         self.advance();
 
         if Token::Newline != self.current_token {
-            self.panic_loc("Expected newline '\\n' at the end of a const declaration.")
+            return Err(self.error("Expected newline '\\n' at the end of a const declaration."))
         }
         self.advance();
-        ASTNode {
+        Ok(ASTNode {
             loc: self.get_current_loc(),
             node_type: ASTNodeType::Const(identifier, const_type, value),
-        }
+        })
     }
 }
@@ -1,5 +1,9 @@
+use std::fmt;
+
 use phf::phf_map;
 
+use crate::diagnostic::Span;
+
 //////////////////
 /// TOKENS
 //////////////////
@@ -10,9 +14,14 @@ pub enum Token {
     Identifier(String),
     Integer(String),
     Float(String),
+    String(String), // the value with escape sequences already resolved
+    FString(Vec<FStringPart>),
     Operator(Operator, String),
     LParen,
     RParen,
+    LBracket,
+    RBracket,
+    Dot,
     Comma,
     Newline,
     Indent(usize),
@@ -20,7 +29,40 @@ pub enum Token {
     EOF,
 }
 
-pub type LocToken = ((usize, usize), Token);
+// How a token is named in diagnostics
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Keyword(keyword) => write!(f, "'{}'", format!("{:?}", keyword).to_lowercase()),
+            Token::Builtin(s) |
+            Token::Identifier(s) |
+            Token::Integer(s) |
+            Token::Float(s) |
+            Token::Operator(_, s) => write!(f, "'{}'", s),
+            Token::String(_) => write!(f, "a string"),
+            Token::FString(_) => write!(f, "an f-string"),
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::LBracket => write!(f, "'['"),
+            Token::RBracket => write!(f, "']'"),
+            Token::Dot => write!(f, "'.'"),
+            Token::Comma => write!(f, "','"),
+            Token::Colon => write!(f, "':'"),
+            Token::Newline => write!(f, "newline"),
+            Token::Indent(_) => write!(f, "indentation"),
+            Token::EOF => write!(f, "end of file"),
+        }
+    }
+}
+
+pub type LocToken = (Span, Token);
+
+// The lexer keeps the expressions of an f-string as text, the parser parses them on their own
+#[derive(Debug, PartialEq, Clone, PartialOrd)]
+pub enum FStringPart {
+    Text(String),
+    Expression(String, Option<String>, (usize, usize)), // (source, format spec, location of the source)
+}
 
 ////////////////
 // OPERATORS
@@ -32,6 +74,12 @@ pub enum Precedences {
     P2,
     P3,
     P4,
+    P5,
+    P6,
+    P7,
+    P8,
+    P9,
+    P10,
     Count,
 }
 
@@ -42,7 +90,13 @@ impl Precedences {
             Precedences::P1 => Precedences::P2,
             Precedences::P2 => Precedences::P3,
             Precedences::P3 => Precedences::P4,
-            Precedences::P4 => Precedences::Count,
+            Precedences::P4 => Precedences::P5,
+            Precedences::P5 => Precedences::P6,
+            Precedences::P6 => Precedences::P7,
+            Precedences::P7 => Precedences::P8,
+            Precedences::P8 => Precedences::P9,
+            Precedences::P9 => Precedences::P10,
+            Precedences::P10 => Precedences::Count,
             Precedences::Count => panic!("Tried to increment Precedences over max!"),
         }
     }
@@ -54,42 +108,126 @@ pub enum Operator {
     Minus,
     Mul,
     Div,
+    FloorDiv,
+    Mod,
+    Power,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
     Equal,
+    NotEqual,
     Greater,
+    GreaterEqual,
     Less,
+    LessEqual,
+    Is,
+    IsNot,
     And,
     Or,
+    Not,
+    BitNot,
     Assignment,
+    AugmentedAssignment, // '+=', '-=', ...: the operator is the symbol without the '='
     ThinArrow,
 }
-pub const OPERATOR_SYMBOLS: [char; 10] = ['!','*','+','-','/','=','<','>','&','|'];
+pub const OPERATOR_SYMBOLS: [char; 13] = ['!','*','+','-','/','=','<','>','&','|','~','%','^'];
 pub const OPERATOR_MAP: phf::Map<&str, Operator> = phf_map! {
     "=" => Operator::Assignment,
+    "+=" => Operator::AugmentedAssignment,
+    "-=" => Operator::AugmentedAssignment,
+    "*=" => Operator::AugmentedAssignment,
+    "/=" => Operator::AugmentedAssignment,
+    "//=" => Operator::AugmentedAssignment,
+    "%=" => Operator::AugmentedAssignment,
+    "**=" => Operator::AugmentedAssignment,
+    "&=" => Operator::AugmentedAssignment,
+    "|=" => Operator::AugmentedAssignment,
+    "^=" => Operator::AugmentedAssignment,
+    "<<=" => Operator::AugmentedAssignment,
+    ">>=" => Operator::AugmentedAssignment,
     "->" => Operator::ThinArrow,
     "&&" => Operator::And,
     "||" => Operator::Or,
+    "and" => Operator::And,
+    "or" => Operator::Or,
+    "is" => Operator::Is,
     "==" => Operator::Equal,
+    "!=" => Operator::NotEqual,
     ">" => Operator::Greater,
+    ">=" => Operator::GreaterEqual,
     "<" => Operator::Less,
+    "<=" => Operator::LessEqual,
     "+" => Operator::Plus,
     "-" => Operator::Minus,
     "*" => Operator::Mul,
     "/" => Operator::Div,
+    "//" => Operator::FloorDiv,
+    "%" => Operator::Mod,
+    "**" => Operator::Power,
+    "&" => Operator::BitAnd,
+    "|" => Operator::BitOr,
+    "^" => Operator::BitXor,
+    "<<" => Operator::ShiftLeft,
+    ">>" => Operator::ShiftRight,
+    "~" => Operator::BitNot,
 };
-pub const OPERATOR_PRECEDENCES: phf::Map<&str, Precedences> = phf_map! {
-    "=" => Precedences::P0,
-    "->" => Precedences::P0,
-    "&&" => Precedences::P1,
-    "||" => Precedences::P1,
-    "==" => Precedences::P2,
-    ">" => Precedences::P2,
-    "<" => Precedences::P2,
-    "+" => Precedences::P3,
-    "-" => Precedences::P3,
-    "*" => Precedences::P4,
-    "/" => Precedences::P4,
+impl Operator {
+    pub fn is_comparison(&self) -> bool {
+        matches!(self,
+            Operator::Equal | Operator::NotEqual |
+            Operator::Greater | Operator::GreaterEqual |
+            Operator::Less | Operator::LessEqual |
+            Operator::Is | Operator::IsNot)
+    }
+}
+
+// Decides how operators of the same precedence group.
+// Left:  a - b - c => (a - b) - c
+// Right: a = b = c => a = (b = c)
+#[derive(Debug, PartialEq, Clone)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
+pub const OPERATOR_PRECEDENCES: phf::Map<&str, (Precedences, Associativity)> = phf_map! {
+    "=" => (Precedences::P0, Associativity::Right),
+    "->" => (Precedences::P0, Associativity::Right),
+    "||" => (Precedences::P1, Associativity::Left),
+    "or" => (Precedences::P1, Associativity::Left),
+    "&&" => (Precedences::P2, Associativity::Left),
+    "and" => (Precedences::P2, Associativity::Left),
+    "==" => (Precedences::P3, Associativity::Left),
+    "!=" => (Precedences::P3, Associativity::Left),
+    ">" => (Precedences::P3, Associativity::Left),
+    ">=" => (Precedences::P3, Associativity::Left),
+    "<" => (Precedences::P3, Associativity::Left),
+    "<=" => (Precedences::P3, Associativity::Left),
+    "is" => (Precedences::P3, Associativity::Left),
+    "|" => (Precedences::P4, Associativity::Left),
+    "^" => (Precedences::P5, Associativity::Left),
+    "&" => (Precedences::P6, Associativity::Left),
+    "<<" => (Precedences::P7, Associativity::Left),
+    ">>" => (Precedences::P7, Associativity::Left),
+    "+" => (Precedences::P8, Associativity::Left),
+    "-" => (Precedences::P8, Associativity::Left),
+    "*" => (Precedences::P9, Associativity::Left),
+    "/" => (Precedences::P9, Associativity::Left),
+    "//" => (Precedences::P9, Associativity::Left),
+    "%" => (Precedences::P9, Associativity::Left),
+    "**" => (Precedences::P10, Associativity::Right),
 };
 
+// 'not' binds looser than comparisons but tighter than 'and': 'not a == b and c' => '(not (a == b)) and c'.
+pub const NOT_PRECEDENCE: Precedences = Precedences::P3;
+pub const COMPARISON_PRECEDENCE: Precedences = Precedences::P3;
+// 'a if cond else b' binds looser than every other operator.
+pub const CONDITIONAL_PRECEDENCE: Precedences = Precedences::P0;
+// '-' and '~' bind tighter than every binary operator but '**': '-2 ** 2' => '-(2 ** 2)'.
+pub const UNARY_PRECEDENCE: Precedences = Precedences::P10;
+
 //////////////////////
 /// KEYWORDS
 //////////////////////
@@ -98,12 +236,19 @@ pub enum Keyword {
     Def,
     Var,
     If,
+    Elif,
     Else,
     While,
+    For,
+    In,
+    Break,
+    Continue,
     True,
     False,
     Return,
     Const,
+    Not,
+    Class,
 }
 
 pub fn match_keywords(s: &str) -> Option<Keyword> {
@@ -111,12 +256,19 @@ pub fn match_keywords(s: &str) -> Option<Keyword> {
         "def" => Some(Keyword::Def),
         "var" => Some(Keyword::Var),
         "if" => Some(Keyword::If),
+        "elif" => Some(Keyword::Elif),
         "else" => Some(Keyword::Else),
         "while" => Some(Keyword::While),
+        "for" => Some(Keyword::For),
+        "in" => Some(Keyword::In),
+        "break" => Some(Keyword::Break),
+        "continue" => Some(Keyword::Continue),
         "true" => Some(Keyword::True),
         "false" => Some(Keyword::False),
         "return" => Some(Keyword::Return),
         "const" => Some(Keyword::Const),
+        "not" => Some(Keyword::Not),
+        "class" => Some(Keyword::Class),
         _ => None,
     }
 }
@@ -126,7 +278,12 @@ pub fn match_keywords(s: &str) -> Option<Keyword> {
 ///////////////////////
 pub fn match_builtin_functions(s: &str) -> Option<String> {
     match s {
+        "print" => Some(String::from("print")),
         "print_int" => Some(String::from("print_int")),
+        "print_float" => Some(String::from("print_float")),
+        "print_bool" => Some(String::from("print_bool")),
+        "print_str" => Some(String::from("print_str")),
+        "len" => Some(String::from("len")),
         _ => None,
     }
 }
@@ -1,39 +1,52 @@
-use std::{collections::HashMap, mem};
+use std::{collections::HashMap, mem, ops::Deref};
 use std::mem::replace;
 
-use crate::ast::{ASTNode, ASTNodeType, PrimitiveTypes, ConstLiteral};
+use crate::ast::{ASTNode, ASTNodeType, PrimitiveTypes, ConstLiteral, Interpolation};
+use crate::diagnostic::{Diagnostic, Span};
 use crate::token::Operator;
 
+// (fields with their type and offset, size of the class)
+type ClassLayout = (Vec<(String, PrimitiveTypes, usize)>, usize);
+
 pub struct TypeChecker {
   scopes: Vec<HashMap<String, (String, PrimitiveTypes)>>,
   var_types: HashMap<String, PrimitiveTypes>,
   var_ref_count: HashMap<String, usize>,
   functions: HashMap<String, (Vec<PrimitiveTypes>, Option<PrimitiveTypes>)>,
-  current_function_return_type: Option<PrimitiveTypes>
+  classes: HashMap<String, ClassLayout>,
+  current_function_return_type: Option<PrimitiveTypes>,
+  loop_depth: usize,
+  file_name: String,
 }
 
 impl Default for TypeChecker {
     fn default() -> Self {
-      Self::new()
+      Self::new(String::new())
     }
 }
 
 impl TypeChecker {
-  pub fn new() -> Self {
+  pub fn new(file_name: String) -> Self {
     TypeChecker {
       scopes: Vec::new(),
       var_types: HashMap::new(),
       functions: HashMap::new(),
+      classes: HashMap::new(),
       var_ref_count: HashMap::new(),
       current_function_return_type: None,
+      loop_depth: 0,
+      file_name,
     }
   }
 
-  pub fn prepare_ast(&mut self, ast: &mut Vec<ASTNode>) {
-    self.register_functions(ast);
-    println!("{:?}", self.functions);
-    self.rename_global_variables(ast);
-    self.resolve_types(ast);
+  pub fn prepare_ast(&mut self, ast: &mut Vec<ASTNode>) -> Result<(), Diagnostic> {
+    self.register_functions(ast)?;
+    self.rename_global_variables(ast)?;
+    self.resolve_types(ast)
+  }
+
+  fn error(&self, loc: (usize, usize), msg: impl Into<String>) -> Diagnostic {
+    Diagnostic::error(&self.file_name, Span::at(loc), msg)
   }
 
   fn advance_ref_counter(&mut self, name: String) -> Option<usize> {
@@ -46,50 +59,101 @@ impl TypeChecker {
   }
 
   fn declare_var(&mut self, name: String, value_type: PrimitiveTypes) -> String {
-    if self.scopes.is_empty() {
-      panic!()
-    }
+    let new_name = match self.advance_ref_counter(name.clone()) {
+      Some(n) => format!("{}_{}", name, n),
+      None => name.clone(),
+    };
+    let Some(scope) = self.scopes.last_mut() else {
+      unreachable!("the rename pass opens a scope before it declares '{}'", name)
+    };
+    scope.insert(name, (new_name.clone(), value_type.clone()));
+    self.var_types.insert(new_name.clone(), value_type);
+    new_name
+  }
 
-    if let Some(n) = self.advance_ref_counter(name.clone()) {
-      let new_name = format!("{}_{}", name, n);
-      self.scopes.last_mut().unwrap().insert(name, (new_name.clone(), value_type.clone()));
-      self.var_types.insert(new_name.clone(), value_type);
-      new_name
+  // The fields are placed in order, each one aligned to its own size. The size of the class is
+  // rounded up to its biggest field, like a C struct.
+  fn declare_class(&mut self, loc: (usize, usize), name: &str, fields: &[(String, PrimitiveTypes, (usize, usize))]) -> Result<(), Diagnostic> {
+    if self.classes.contains_key(name) {
+      return Err(self.error(loc, format!("Class '{}' is already defined", name)));
     }
-    else {
-      self.scopes.last_mut().unwrap().insert(name.clone(), (name.clone(), value_type.clone()));
-      self.var_types.insert(name.clone(), value_type);
-      name
+    let mut layout: Vec<(String, PrimitiveTypes, usize)> = Vec::new();
+    let mut offset: usize = 0;
+    let mut align: usize = 1;
+    for (field, typ, field_loc) in fields {
+      if layout.iter().any(|(other, _, _)| other == field) {
+        return Err(self.error(*field_loc, format!("The field '{}' is already part of '{}'", field, name)));
+      }
+      if !typ.is_integer() && !matches!(typ, PrimitiveTypes::F64 | PrimitiveTypes::Bool | PrimitiveTypes::Str) {
+        return Err(self.error(*field_loc, format!("The field '{}' of '{}' has to be a number, bool or str, but got '{}'", field, name, typ)));
+      }
+      offset = offset.next_multiple_of(typ.size());
+      layout.push((field.clone(), typ.clone(), offset));
+      offset += typ.size();
+      align = align.max(typ.size());
+    }
+    if layout.is_empty() {
+      return Err(self.error(loc, format!("Class '{}' needs at least one field", name)));
     }
+    self.classes.insert(name.to_string(), (layout, offset.next_multiple_of(align)));
+    Ok(())
   }
 
-  fn declare_parameters(&mut self, parameters: &mut Vec<(String, PrimitiveTypes)>) {
-    if self.scopes.is_empty() {
-      panic!()
+  // The parser only knows the name of a class, its size comes from the layout
+  fn class_type(&self, loc: (usize, usize), typ: &PrimitiveTypes) -> Result<PrimitiveTypes, Diagnostic> {
+    match typ {
+      PrimitiveTypes::Struct(name, _) => {
+        let Some((_, size)) = self.classes.get(name) else {
+          return Err(self.error(loc, format!("Class '{}' was not defined", name)));
+        };
+        Ok(PrimitiveTypes::Struct(name.clone(), *size))
+      }
+      PrimitiveTypes::Array(element, _) if matches!(**element, PrimitiveTypes::Struct(_, _)) => {
+        Err(self.error(loc, "Arrays of classes are not supported yet"))
+      }
+      typ => Ok(typ.clone()),
     }
-    if !self.scopes.last().unwrap().is_empty() {
-      panic!("Expected last scope to be emoty for parameters");
+  }
+
+  // 'p.x': the type and offset of the field 'x' in the class of 'p'
+  fn find_field(&self, loc: (usize, usize), instance: &ASTNode, field: &str) -> Result<(PrimitiveTypes, usize), Diagnostic> {
+    let ASTNodeType::Identifier(ref name, ref typ) = instance.node_type else {
+      return Err(self.error(loc, "Only instances of classes have fields"));
+    };
+    let PrimitiveTypes::Struct(ref class, _) = typ else {
+      return Err(self.error(loc, format!("'{}' is not an instance of a class, but of type '{}'", name, typ)));
+    };
+    let Some((fields, _)) = self.classes.get(class) else {
+      return Err(self.error(loc, format!("Class '{}' was not defined", class)));
+    };
+    let Some((_, field_type, offset)) = fields.iter().find(|(other, _, _)| other == field) else {
+      return Err(self.error(loc, format!("Class '{}' has no field '{}'", class, field)));
+    };
+    Ok((field_type.clone(), *offset))
+  }
+
+  fn declare_parameters(&mut self, loc: (usize, usize), parameters: &mut Vec<(String, PrimitiveTypes)>) -> Result<(), Diagnostic> {
+    if !self.scopes.last().is_some_and(|scope| scope.is_empty()) {
+      return Err(self.error(loc, "Expected an empty scope for the parameters"));
     }
     if parameters.is_empty() {
-      return;
+      return Ok(());
     }
 
     let mut dup: Vec<String> = Vec::new();
     // check for duplicate names
     for (name, value_type) in parameters {
       if dup.contains(name) {
-        panic!("Duplicate parameter name '{}'", name);
+        return Err(self.error(loc, format!("Duplicate parameter name '{}'", name)));
       }
       let new_name = self.declare_var(name.clone(), value_type.clone());
       let old_name = replace(name, new_name);
       dup.push(old_name);
     }
+    Ok(())
   }
 
   fn get_var(&self, name: &String) -> Option<(String, PrimitiveTypes)> {
-    if self.scopes.is_empty() {
-      panic!()
-    }
     for scope in self.scopes.iter().rev() {
       if let Some(var) = scope.get(name) {
         return Some(var.clone());
@@ -105,11 +169,11 @@ impl TypeChecker {
     None
   }
 
-  fn register_functions(&mut self, ast: &Vec<ASTNode>) {
+  fn register_functions(&mut self, ast: &Vec<ASTNode>) -> Result<(), Diagnostic> {
     for node in ast {
       if let ASTNodeType::FunctionDef(ref name, ref args, ref return_type, _) = node.node_type {
         if self.functions.contains_key(name) {
-          panic!("double function delcaration '{name}'")
+          return Err(self.error(node.loc, format!("double function delcaration '{name}'")))
         }
         let mut arg_types = Vec::new();
         if let Some(args) = args {
@@ -120,248 +184,558 @@ impl TypeChecker {
         self.functions.insert(name.clone(), (arg_types, return_type.clone()));
       }
     }
+    Ok(())
   }
 
-  fn rename_global_variables(&mut self, ast: &mut Vec<ASTNode>) {
-    self.rename_global_variables_statements(ast);
+  fn rename_global_variables(&mut self, ast: &mut Vec<ASTNode>) -> Result<(), Diagnostic> {
+    self.rename_global_variables_statements(ast)
   }
 
-  fn rename_global_variables_statements(&mut self, ast: &mut Vec<ASTNode>) {
+  fn rename_global_variables_statements(&mut self, ast: &mut Vec<ASTNode>) -> Result<(), Diagnostic> {
     self.scopes.push(HashMap::new());
     for node in ast {
+      let loc = node.loc;
       match node.node_type {
         ASTNodeType::FunctionDef(_, ref mut args, _, ref mut body) => {
           self.scopes.push(HashMap::new());
           if let Some(args) = args {
-            self.declare_parameters(args);
+            self.declare_parameters(loc, args)?;
           }
-          self.rename_global_variables_statements(body);
+          self.rename_global_variables_statements(body)?;
           self.scopes.pop();
         }
         ASTNodeType::Assignment(ref mut name, ref mut value) => {
-          self.rename_global_variables_expression(value);
+          self.rename_global_variables_expression(value)?;
           let Some((new_name, _)) = self.get_var(name) else {
-            panic!("Var '{}' was not declared", name);
+            return Err(self.error(loc, format!("Var '{}' was not declared", name)));
           };
           let _ = mem::replace(name, new_name);
         },
-        ASTNodeType::SExpression(ref mut expr) => self.rename_global_variables_expression(expr),
-        ASTNodeType::BuiltinFunction(_, ref mut expr) => {
+        ASTNodeType::IndexAssignment(ref mut array, ref mut index, ref mut value) => {
+          self.rename_global_variables_expression(value)?;
+          self.rename_global_variables_expression(array)?;
+          self.rename_global_variables_expression(index)?;
+        },
+        ASTNodeType::FieldAssignment(ref mut field, ref mut value) => {
+          self.rename_global_variables_expression(value)?;
+          self.rename_global_variables_expression(field)?;
+        },
+        ASTNodeType::SExpression(ref mut expr) => self.rename_global_variables_expression(expr)?,
+        ASTNodeType::BuiltinFunction(_, ref mut args) => {
           // println!("WARNING: BuiltIn function are pure statements atm. this will change!");
-          // println!("WARNING: BuiltIn function arguments are perceived as on expression, not actual arguments!");
-          self.rename_global_variables_expression(expr);
+          for expr in args {
+            self.rename_global_variables_expression(expr)?;
+          }
         },
-        ASTNodeType::Declaration(ref mut name, ref value_type, ref mut value) => {
+        ASTNodeType::Declaration(ref mut name, ref mut value_type, ref mut value) => {
           if let Some(value) = value {
-            self.rename_global_variables_expression(value);
+            self.rename_global_variables_expression(value)?;
+            // 'var x = expr' without a type
+            if *value_type == PrimitiveTypes::Void {
+              let found_type = self.find_operant_type(value)?;
+              let Some(inferred_type) = TypeChecker::inferred_type(&found_type) else {
+                return Err(self.error(loc, format!("Can not infer the type of '{}' from a value of type '{}'. Declare it with 'var {}: TYPE = ...'", name, found_type, name)));
+              };
+              let _ = mem::replace(value_type, inferred_type);
+            }
           }
+          let _ = mem::replace(value_type, self.class_type(loc, value_type)?);
           let new_name = self.declare_var(name.clone(), value_type.clone());
           let _ = mem::replace(name, new_name);
         },
+        ASTNodeType::Class(ref name, ref fields) => self.declare_class(loc, name, fields)?,
         ASTNodeType::Const(ref mut name, ref const_type, _) => {
           let new_name = self.declare_var(name.clone(), const_type.clone());
           let _ = mem::replace(name, new_name);
         }
         ASTNodeType::If(ref mut cond, ref mut then, ref mut els) => {
-          self.rename_global_variables_expression(cond);
-          self.rename_global_variables_statements(then);
+          self.rename_global_variables_expression(cond)?;
+          self.rename_global_variables_statements(then)?;
           if let Some(els) = els {
-            self.rename_global_variables_statements(els);
+            self.rename_global_variables_statements(els)?;
           }
         },
         ASTNodeType::While(ref mut cond, ref mut body) => {
-          self.rename_global_variables_expression(cond);
-          self.rename_global_variables_statements(body);
+          self.rename_global_variables_expression(cond)?;
+          self.rename_global_variables_statements(body)?;
+        },
+        ASTNodeType::For(ref mut name, ref mut var_type, ref mut start, ref mut stop, ref mut step, ref mut body) => {
+          // the loop variable gets the type of start and stop. Ranges of only literals are 'i64',
+          // so they can count down. The step has its own type, see the resolve pass.
+          let mut range_type = PrimitiveTypes::Integer;
+          for expr in [&mut **start, &mut **stop] {
+            self.rename_global_variables_expression(expr)?;
+            let found_type = self.find_operant_type(expr)?;
+            range_type = self.dominant_type(expr.loc, &range_type, &found_type)?.clone();
+          }
+          self.rename_global_variables_expression(step)?;
+          let _ = mem::replace(var_type, match range_type {
+            PrimitiveTypes::Integer => PrimitiveTypes::I64,
+            typ => typ,
+          });
+
+          // the loop variable only lives inside the loop
+          self.scopes.push(HashMap::new());
+          let new_name = self.declare_var(name.clone(), var_type.clone());
+          let _ = mem::replace(name, new_name);
+          self.rename_global_variables_statements(body)?;
+          self.scopes.pop();
+        },
+        ASTNodeType::ForEach(ref mut name, ref mut element_type, ref mut array, ref mut body) => {
+          self.rename_global_variables_expression(array)?;
+          if let ASTNodeType::Identifier(ref array_name, ref array_type) = array.node_type {
+            let PrimitiveTypes::Array(ref element, _) = array_type else {
+              return Err(self.error(array.loc, format!("Only ranges and arrays can be iterated over, but '{}' is of type '{}'", array_name, array_type)));
+            };
+            let _ = mem::replace(element_type, element.deref().clone());
+          }
+
+          self.scopes.push(HashMap::new());
+          let new_name = self.declare_var(name.clone(), element_type.clone());
+          let _ = mem::replace(name, new_name);
+          self.rename_global_variables_statements(body)?;
+          self.scopes.pop();
         },
         ASTNodeType::Return(Some(ref mut expr)) => {
-          self.rename_global_variables_expression(expr);
+          self.rename_global_variables_expression(expr)?;
         }
         ASTNodeType::Return(None) => {}
+        ASTNodeType::Break |
+        ASTNodeType::Continue => {}
+        ASTNodeType::Error => {
+          return Err(self.error(loc, "Can not check a statement that failed to parse"));
+        }
 
         ASTNodeType::FunctionCall(_, _, _) |
         ASTNodeType::Literal(_, _) |
         ASTNodeType::Identifier(_, _) |
-        ASTNodeType::BinaryOp(_, _, _, _) => {
-          panic!("Unexpected Expression as Statement!");
+        ASTNodeType::BinaryOp(_, _, _, _) |
+        ASTNodeType::UnaryOp(_, _, _) |
+        ASTNodeType::Cast(_, _) |
+        ASTNodeType::Conditional(_, _, _, _) |
+        ASTNodeType::ChainedComparison(_, _) |
+        ASTNodeType::Len(_) |
+        ASTNodeType::FString(_) |
+        ASTNodeType::Index(_, _, _) |
+        ASTNodeType::Field(_, _, _, _) |
+        ASTNodeType::List(_, _) => {
+          return Err(self.error(loc, "Unexpected Expression as Statement!"));
         },
       }
     }
     self.scopes.pop();
+    Ok(())
   }
 
-  fn rename_global_variables_expression(&mut self, node: &mut ASTNode) {
+  fn rename_global_variables_expression(&mut self, node: &mut ASTNode) -> Result<(), Diagnostic> {
+    let loc = node.loc;
     match node.node_type {
       ASTNodeType::BinaryOp(ref mut left, _, ref mut right, _) => {
-        self.rename_global_variables_expression(left);
-        self.rename_global_variables_expression(right);
+        self.rename_global_variables_expression(left)?;
+        self.rename_global_variables_expression(right)?;
+      },
+      ASTNodeType::UnaryOp(_, ref mut operand, _) => self.rename_global_variables_expression(operand)?,
+      ASTNodeType::Cast(ref mut value, _) => self.rename_global_variables_expression(value)?,
+      ASTNodeType::Len(ref mut value) => self.rename_global_variables_expression(value)?,
+      ASTNodeType::Index(ref mut array, ref mut index, ref mut element_type) => {
+        self.rename_global_variables_expression(array)?;
+        self.rename_global_variables_expression(index)?;
+        if let ASTNodeType::Identifier(_, PrimitiveTypes::Array(ref element, _)) = array.node_type {
+          let _ = mem::replace(element_type, element.deref().clone());
+        }
+      },
+      ASTNodeType::FString(ref mut parts) => {
+        for part in parts {
+          if let Interpolation::Value(ref mut value, _) = part {
+            self.rename_global_variables_expression(value)?;
+          }
+        }
+      },
+      ASTNodeType::ChainedComparison(ref mut operands, _) => {
+        for operand in operands {
+          self.rename_global_variables_expression(operand)?;
+        }
+      },
+      ASTNodeType::List(ref mut elements, _) => {
+        for element in elements {
+          self.rename_global_variables_expression(element)?;
+        }
+      },
+      ASTNodeType::Field(ref mut instance, ref field, ref mut field_type, ref mut offset) => {
+        self.rename_global_variables_expression(instance)?;
+        let (found_type, found_offset) = self.find_field(loc, instance, field)?;
+        let _ = mem::replace(field_type, found_type);
+        let _ = mem::replace(offset, found_offset);
+      },
+      ASTNodeType::Conditional(ref mut cond, ref mut then, ref mut els, _) => {
+        self.rename_global_variables_expression(cond)?;
+        self.rename_global_variables_expression(then)?;
+        self.rename_global_variables_expression(els)?;
       },
       ASTNodeType::Literal(_, _) => {},
       ASTNodeType::Identifier(ref mut name, ref mut value_type) => {
         let Some((new_name, new_value_type)) = self.get_var(name) else {
-          panic!("Var '{}' was not declared", name);
+          return Err(self.error(loc, format!("Var '{}' was not declared", name)));
         };
         let _ = mem::replace(name, new_name);
         let _ = mem::replace(value_type, new_value_type);
       },
       ASTNodeType::FunctionCall(_, ref mut args, _) => {
         for expr in args {
-          self.rename_global_variables_expression(expr);
+          self.rename_global_variables_expression(expr)?;
         }
       },
 
       ASTNodeType::Return(_) |
       ASTNodeType::FunctionDef(_, _, _, _) |
       ASTNodeType::Assignment(_, _) |
+      ASTNodeType::IndexAssignment(_, _, _) |
+      ASTNodeType::FieldAssignment(_, _) |
       ASTNodeType::BuiltinFunction(_, _) |
       ASTNodeType::Declaration(_, _, _) |
       ASTNodeType::Const(_, _, _) |
+      ASTNodeType::Class(_, _) |
       ASTNodeType::If(_, _, _) |
       ASTNodeType::While(_, _) |
-      ASTNodeType::SExpression(_) => {
-        panic!("Unexpected statement while renaming vars in expressions {:?}", node);
+      ASTNodeType::For(_, _, _, _, _, _) |
+      ASTNodeType::ForEach(_, _, _, _) |
+      ASTNodeType::Break |
+      ASTNodeType::Continue |
+      ASTNodeType::SExpression(_) |
+      ASTNodeType::Error => {
+        return Err(self.error(loc, "Unexpected statement while renaming vars in expressions"));
       }
     }
+    Ok(())
   }
 
-  fn resolve_types(&mut self, ast: &mut Vec<ASTNode>) {
-    self.resolve_types_statements(ast);
+  fn resolve_types(&mut self, ast: &mut Vec<ASTNode>) -> Result<(), Diagnostic> {
+    self.resolve_types_statements(ast)
   }
 
-  fn resolve_types_statements(&mut self, ast: &mut Vec<ASTNode>) {
+  fn resolve_types_statements(&mut self, ast: &mut Vec<ASTNode>) -> Result<(), Diagnostic> {
     for node in ast {
+      let loc = node.loc;
       match node.node_type {
         ASTNodeType::FunctionDef(_, _, ref return_type, ref mut body) => {
           self.current_function_return_type = return_type.clone();
-          self.resolve_types_statements(body);
+          let loop_depth = replace(&mut self.loop_depth, 0);
+          self.resolve_types_statements(body)?;
+          self.loop_depth = loop_depth;
           self.current_function_return_type = None;
         }
         ASTNodeType::Assignment(ref name, ref mut value) => {
-          let new_type = self.resolve_types_expression(value);
           let Some(var_type) = self.get_var_type(name) else {
-            panic!("Var '{name}' was not declared but tried to assign to.")
+            return Err(self.error(loc, format!("Var '{name}' was not declared but tried to assign to.")))
           };
-          let dominant_type = TypeChecker::get_dominant_type(&var_type, &new_type);
-          self.set_type_for_expression(value, dominant_type);
+          if let PrimitiveTypes::Array(_, _) = var_type {
+            return Err(self.error(loc, format!("The array '{}' can only be assigned through its elements, like '{}[0] = ...'", name, name)));
+          }
+          if let PrimitiveTypes::Struct(ref class, _) = var_type {
+            return Err(self.error(loc, format!("'{}' is an instance of '{}', assign to its fields instead", name, class)));
+          }
+          let new_type = self.resolve_types_expression(value)?;
+          let dominant_type = self.dominant_type(loc, &var_type, &new_type)?;
+          self.set_type_for_expression(value, dominant_type)?;
         }
-        ASTNodeType::BuiltinFunction(_, ref mut expr) => {
-          let expected_type = PrimitiveTypes::U64;
-          let found_type = self.resolve_types_expression(expr);
-          let dominant_type = TypeChecker::get_dominant_type(&expected_type, &found_type);
-          self.set_type_for_expression(expr, dominant_type);
+        ASTNodeType::IndexAssignment(ref array, ref mut index, ref mut value) => {
+          let element_type = self.resolve_index(loc, array, index)?;
+          let new_type = self.resolve_types_expression(value)?;
+          let dominant_type = self.dominant_type(loc, &element_type, &new_type)?;
+          self.set_type_for_expression(value, dominant_type)?;
         }
-        ASTNodeType::Declaration(_, ref value_type, ref mut value) => {
-          if let Some(value) = value {
-            let expr_type = self.resolve_types_expression(value);
-            let dominant_type = TypeChecker::get_dominant_type(value_type, &expr_type);
+        ASTNodeType::FieldAssignment(ref field, ref mut value) => {
+          let field_type = self.find_operant_type(field)?;
+          let new_type = self.resolve_types_expression(value)?;
+          let dominant_type = self.dominant_type(loc, &field_type, &new_type)?;
+          self.set_type_for_expression(value, dominant_type)?;
+        }
+        ASTNodeType::Class(_, _) => {}
+        ASTNodeType::BuiltinFunction(ref name, ref mut args) => {
+          for expr in args {
+            self.resolve_builtin_argument(loc, name, expr)?;
+          }
+        }
+        ASTNodeType::Declaration(ref name, ref value_type, ref mut value) => {
+          if let (PrimitiveTypes::Struct(class, _), Some(_)) = (value_type, &value) {
+            return Err(self.error(loc, format!("The instance '{}' of '{}' can not be initialized with a value, assign to its fields instead", name, class)));
+          }
+          if let (PrimitiveTypes::Array(_, _), Some(value)) = (value_type, &mut *value) {
+            self.resolve_list(name, value_type, value)?;
+          }
+          else if let Some(value) = value {
+            let expr_type = self.resolve_types_expression(value)?;
+            let dominant_type = self.dominant_type(loc, value_type, &expr_type)?;
             if value_type != dominant_type {
-              panic!("mismatch in types during Declaration {:#?} {:#?}", value_type, expr_type);
+              return Err(self.error(loc, format!("mismatch in types during Declaration '{}' and '{}'", value_type, expr_type)));
             }
-            self.set_type_for_expression(value, dominant_type);
+            self.set_type_for_expression(value, dominant_type)?;
           }
         }
         ASTNodeType::Const(_, ref const_type, ref mut value) => {
           match (const_type, value) {
             // valid combinations
+            (int_type, ConstLiteral::Integer(ref value)) if int_type.is_integer() => {
+              self.check_literal_range(loc, value, int_type)?;
+            }
             (PrimitiveTypes::F64, ConstLiteral::Float(_)) |
-            (PrimitiveTypes::U64, ConstLiteral::Integer(_)) |
             (PrimitiveTypes::Bool, ConstLiteral::Bool(_)) => {}
 
             // valid const type but mismatch of types
-            (PrimitiveTypes::U64, _) |
-            (PrimitiveTypes::F64, _) |
-            (PrimitiveTypes::Bool, _) => panic!(),
+            (PrimitiveTypes::U64, value) |
+            (PrimitiveTypes::U32, value) |
+            (PrimitiveTypes::U16, value) |
+            (PrimitiveTypes::U8, value) |
+            (PrimitiveTypes::I64, value) |
+            (PrimitiveTypes::I32, value) |
+            (PrimitiveTypes::I16, value) |
+            (PrimitiveTypes::I8, value) |
+            (PrimitiveTypes::F64, value) |
+            (PrimitiveTypes::Bool, value) => {
+              return Err(self.error(loc, format!("Const of type '{}' can not be initialized with {:?}", const_type, value)));
+            }
 
             // ambiguous/invalid types for a const
             (PrimitiveTypes::Number, _) |
             (PrimitiveTypes::Float, _) |
             (PrimitiveTypes::Integer, _) |
             (PrimitiveTypes::Void, _) |
-            (PrimitiveTypes::COUNT, _) => panic!(),
+            (PrimitiveTypes::Str, _) |
+            (PrimitiveTypes::Array(_, _), _) |
+            (PrimitiveTypes::Struct(_, _), _) |
+            (PrimitiveTypes::COUNT, _) => {
+              return Err(self.error(loc, format!("'{}' is not a valid type for a const", const_type)));
+            }
           }
         }
         ASTNodeType::If(ref mut cond, ref mut then, ref mut els) => {
-          let expected_type = PrimitiveTypes::U64;
-          let found_type = self.resolve_types_expression(cond);
-          let dominant_type = TypeChecker::get_dominant_type(&expected_type, &found_type);
-          self.set_type_for_expression(cond, dominant_type);
-          self.resolve_types_statements(then);
+          self.resolve_condition(loc, cond)?;
+          self.resolve_types_statements(then)?;
           if let Some(els) = els {
-            self.resolve_types_statements(els);
+            self.resolve_types_statements(els)?;
           }
         }
         ASTNodeType::While(ref mut cond, ref mut body) => {
           // let dominant_type = TypeChecker::get_dominant_type(&expected_type, &found_type);
-          self.set_type_for_expression(cond, &PrimitiveTypes::Bool);
-          self.resolve_types_statements(body);
+          self.set_type_for_expression(cond, &PrimitiveTypes::Bool)?;
+          self.loop_depth += 1;
+          self.resolve_types_statements(body)?;
+          self.loop_depth -= 1;
+        }
+        ASTNodeType::For(_, ref var_type, ref mut start, ref mut stop, ref mut step, ref mut body) => {
+          if !var_type.is_integer() {
+            return Err(self.error(loc, format!("range() only works with integers, but found '{}'", var_type)));
+          }
+          for expr in [start, stop] {
+            let found_type = self.resolve_types_expression(expr)?;
+            let dominant_type = self.dominant_type(expr.loc, var_type, &found_type)?;
+            self.set_type_for_expression(expr, dominant_type)?;
+          }
+          // a literal step is signed, so 'range(n, 0, -1)' counts down from an unsigned 'n'
+          let step_type = match self.resolve_types_expression(step)? {
+            PrimitiveTypes::Number |
+            PrimitiveTypes::Integer if var_type.is_signed() => var_type.clone(),
+            PrimitiveTypes::Number |
+            PrimitiveTypes::Integer => PrimitiveTypes::I64,
+            typ if typ.is_integer() => typ,
+            typ => return Err(self.error(step.loc, format!("The step of range() has to be an integer, but got '{}'", typ))),
+          };
+          self.set_type_for_expression(step, &step_type)?;
+          if let ASTNodeType::Literal(_, ref value) = step.node_type {
+            if value.parse::<i128>() == Ok(0) {
+              return Err(self.error(step.loc, "range() step must not be zero"));
+            }
+          }
+          self.loop_depth += 1;
+          self.resolve_types_statements(body)?;
+          self.loop_depth -= 1;
+        }
+        ASTNodeType::ForEach(_, _, _, ref mut body) => {
+          self.loop_depth += 1;
+          self.resolve_types_statements(body)?;
+          self.loop_depth -= 1;
+        }
+        ASTNodeType::Break => {
+          if self.loop_depth == 0 {
+            return Err(self.error(loc, "'break' outside of a loop"));
+          }
+        }
+        ASTNodeType::Continue => {
+          if self.loop_depth == 0 {
+            return Err(self.error(loc, "'continue' outside of a loop"));
+          }
         }
         ASTNodeType::SExpression(ref mut expr) => {
-          let _ = self.resolve_types_expression(expr);
+          let _ = self.resolve_types_expression(expr)?;
         }
         ASTNodeType::Return(ref mut expr) => {
           match (expr, self.current_function_return_type.clone()) {
             (None, None) => {}, // fine
+            (None, Some(PrimitiveTypes::Void)) => {}, // bare 'return' in a 'void' function
             (Some(ref mut expr), Some(return_type)) => {
-              let found_type = self.resolve_types_expression(expr.as_mut());
-              if found_type != return_type {
-                panic!()
+              let found_type = self.resolve_types_expression(expr.as_mut())?;
+              let dominant_type = self.dominant_type(loc, &return_type, &found_type)?;
+              if *dominant_type != return_type {
+                return Err(self.error(loc, format!("Function returns '{}', but found '{}'", return_type, found_type)));
               }
+              self.set_type_for_expression(expr, &return_type)?;
             },
 
-            (Some(_), None) |
-            (None, Some(_)) => panic!("{:?}", node),
+            (Some(_), None) => return Err(self.error(loc, "Can not return a value outside of a function")),
+            (None, Some(return_type)) => return Err(self.error(loc, format!("Expected a return value of type '{}'", return_type))),
           }
         }
+        ASTNodeType::Error => {
+          return Err(self.error(loc, "Can not check a statement that failed to parse"));
+        }
 
         ASTNodeType::FunctionCall(_, _, _) |
         ASTNodeType::BinaryOp(_, _, _, _) |
+        ASTNodeType::UnaryOp(_, _, _) |
+        ASTNodeType::Cast(_, _) |
+        ASTNodeType::Conditional(_, _, _, _) |
+        ASTNodeType::ChainedComparison(_, _) |
+        ASTNodeType::Len(_) |
+        ASTNodeType::FString(_) |
+        ASTNodeType::Index(_, _, _) |
+        ASTNodeType::Field(_, _, _, _) |
+        ASTNodeType::List(_, _) |
         ASTNodeType::Literal(_, _) |
         ASTNodeType::Identifier(_, _) => {
-          panic!()
+          return Err(self.error(loc, "Unexpected Expression as Statement!"));
         },
       }
     }
+    Ok(())
+  }
+
+  // The value of a cast keeps its own type. Values without an explicit type get the widest
+  // one of their kind.
+  fn resolve_cast(&mut self, loc: (usize, usize), value: &mut ASTNode, target: &PrimitiveTypes) -> Result<(), Diagnostic> {
+    let value_type = match self.resolve_types_expression(value)? {
+      PrimitiveTypes::Number |
+      PrimitiveTypes::Integer => PrimitiveTypes::I64,
+      PrimitiveTypes::Float => PrimitiveTypes::F64,
+      value_type => value_type,
+    };
+    let castable = |typ: &PrimitiveTypes| typ.is_integer() || matches!(typ, PrimitiveTypes::F64 | PrimitiveTypes::Bool);
+    if !castable(&value_type) || !castable(target) {
+      return Err(self.error(loc, format!("Can not cast '{}' to '{}'", value_type, target)));
+    }
+    self.set_type_for_expression(value, &value_type)
   }
 
-  fn resolve_types_expression(&mut self, node: &mut ASTNode) -> PrimitiveTypes{
-    let found_type = self.find_operant_type(node);
+  // 'a[i]': 'a' has to be an array and 'i' an integer. Returns the element type.
+  fn resolve_index(&mut self, loc: (usize, usize), array: &ASTNode, index: &mut ASTNode) -> Result<PrimitiveTypes, Diagnostic> {
+    let ASTNodeType::Identifier(ref name, ref array_type) = array.node_type else {
+      return Err(self.error(loc, "Only arrays can be indexed"));
+    };
+    let PrimitiveTypes::Array(ref element_type, _) = array_type else {
+      return Err(self.error(loc, format!("'{}' is not an array, but of type '{}'", name, array_type)));
+    };
+    let found_type = self.resolve_types_expression(index)?;
+    if !matches!(found_type, PrimitiveTypes::Number | PrimitiveTypes::Integer) && !found_type.is_integer() {
+      return Err(self.error(index.loc, format!("The index of an array has to be an integer, but got '{}'", found_type)));
+    }
+    self.set_type_for_expression(index, &TypeChecker::expected_integer(&found_type))?;
+    Ok(element_type.deref().clone())
+  }
+
+  // 'var a: u64[3] = [1, 2, 3]': the list needs one value for every element of the array
+  fn resolve_list(&mut self, name: &str, array_type: &PrimitiveTypes, value: &mut ASTNode) -> Result<(), Diagnostic> {
+    let loc = value.loc;
+    let PrimitiveTypes::Array(ref element_type, len) = array_type else {
+      return Err(self.error(loc, format!("'{}' is not an array, but of type '{}'", name, array_type)));
+    };
+    let ASTNodeType::List(ref mut elements, ref mut list_type) = value.node_type else {
+      return Err(self.error(loc, format!("The array '{}' can only be initialized with a list, like '[1, 2, 3]'", name)));
+    };
+    if elements.len() != *len {
+      return Err(self.error(loc, format!("The array '{}' has {} elements, but the list has {}", name, len, elements.len())));
+    }
+    for element in elements.iter_mut() {
+      let found_type = self.resolve_types_expression(element)?;
+      let dominant_type = self.dominant_type(element.loc, element_type, &found_type)?.clone();
+      self.set_type_for_expression(element, &dominant_type)?;
+    }
+    let _ = mem::replace(list_type, array_type.clone());
+    Ok(())
+  }
+
+  // The length of a string is its number of bytes. The one of an array is part of its type.
+  fn resolve_len(&mut self, loc: (usize, usize), value: &mut ASTNode) -> Result<(), Diagnostic> {
+    if let ASTNodeType::Identifier(_, PrimitiveTypes::Array(_, _)) = value.node_type {
+      return Ok(());
+    }
+    let value_type = self.resolve_types_expression(value)?;
+    if value_type != PrimitiveTypes::Str {
+      return Err(self.error(loc, format!("'len' is only defined for strings and arrays, but got '{}'", value_type)));
+    }
+    self.set_type_for_expression(value, &value_type)
+  }
+
+  fn resolve_types_expression(&mut self, node: &mut ASTNode) -> Result<PrimitiveTypes, Diagnostic> {
+    let loc = node.loc;
+    let found_type = self.find_operant_type(node)?;
     let new_type: PrimitiveTypes = match node.node_type {
-        ASTNodeType::BinaryOp(ref mut left, Operator::Equal, ref mut right, ref mut op_type) |
-        ASTNodeType::BinaryOp(ref mut left, Operator::Less, ref mut right, ref mut op_type) |
-        ASTNodeType::BinaryOp(ref mut left, Operator::Greater, ref mut right, ref mut op_type) => {
-          self.set_type_for_expression(left, &found_type);
-          self.set_type_for_expression(right, &found_type);
+        ASTNodeType::BinaryOp(ref mut left, ref op @ Operator::Equal, ref mut right, ref mut op_type) |
+        ASTNodeType::BinaryOp(ref mut left, ref op @ Operator::NotEqual, ref mut right, ref mut op_type) |
+        ASTNodeType::BinaryOp(ref mut left, ref op @ Operator::Less, ref mut right, ref mut op_type) |
+        ASTNodeType::BinaryOp(ref mut left, ref op @ Operator::LessEqual, ref mut right, ref mut op_type) |
+        ASTNodeType::BinaryOp(ref mut left, ref op @ Operator::Greater, ref mut right, ref mut op_type) |
+        ASTNodeType::BinaryOp(ref mut left, ref op @ Operator::GreaterEqual, ref mut right, ref mut op_type) |
+        ASTNodeType::BinaryOp(ref mut left, ref op @ Operator::Is, ref mut right, ref mut op_type) |
+        ASTNodeType::BinaryOp(ref mut left, ref op @ Operator::IsNot, ref mut right, ref mut op_type) => {
+          self.resolve_comparison(loc, op, left, right)?;
           let _ = replace(op_type, PrimitiveTypes::Bool);
-          return found_type
+          return Ok(found_type)
+        }
+        ASTNodeType::BinaryOp(_, _, _, _) |
+        ASTNodeType::UnaryOp(_, _, _) => found_type,
+        ASTNodeType::Conditional(ref mut cond, _, _, _) => {
+          self.resolve_condition(loc, cond)?;
+          found_type
+        }
+        ASTNodeType::ChainedComparison(ref mut operands, ref ops) => {
+          self.resolve_chained_comparison(loc, operands, ops)?;
+          found_type
+        }
+        ASTNodeType::Cast(ref mut value, ref target) => {
+          self.resolve_cast(loc, value, target)?;
+          target.clone()
+        }
+        ASTNodeType::Len(ref mut value) => {
+          self.resolve_len(loc, value)?;
+          PrimitiveTypes::U64
+        }
+        ASTNodeType::FString(_) => {
+          return Err(self.error(loc, "f-strings can only be printed directly, like 'print(f\"...\")'"));
+        }
+        ASTNodeType::List(_, _) => {
+          return Err(self.error(loc, "Lists can only initialize arrays, like 'var a: u64[3] = [1, 2, 3]'"));
         }
-        ASTNodeType::BinaryOp(_, _, _, _) => found_type,
         ASTNodeType::Literal(ref typ, _) => typ.clone(),
+        ASTNodeType::Identifier(ref name, PrimitiveTypes::Array(_, _)) => {
+          return Err(self.error(loc, format!("The array '{}' can only be used through its elements, like '{}[0]'", name, name)));
+        }
+        ASTNodeType::Identifier(ref name, PrimitiveTypes::Struct(ref class, _)) => {
+          return Err(self.error(loc, format!("'{}' is an instance of '{}' and can only be used through its fields", name, class)));
+        }
         ASTNodeType::Identifier(_, ref value_type) => {
-          let dominant_type = TypeChecker::get_dominant_type(value_type, &found_type);
+          let dominant_type = self.dominant_type(loc, value_type, &found_type)?;
           dominant_type.clone()
         }
+        ASTNodeType::Index(ref array, ref mut index, _) => self.resolve_index(loc, array, index)?,
+        ASTNodeType::Field(_, _, ref typ, _) => typ.clone(),
         ASTNodeType::FunctionCall(ref name, ref mut args, _) => {
-          if !args.is_empty() {
-            // print!("WARNING: All return values of all functions are none atm.");
-            // print!("WARNING: All functions calls are treated as statements atm.");
-            if !self.functions.contains_key(name) {
-              panic!("Function '{}' was not defined", name);
-            };
-            if args.len() != self.functions.get(name).unwrap().0.len() {
-              panic!("number of arguments and parameter does not match up for '{}'", name);
-            }
-
-            for (i, arg) in args.iter_mut().enumerate() {
-              let found_type = self.resolve_types_expression(arg);
-              let exprected_type = &self.functions.get(name).unwrap().0[i];
-              let dominant_type = TypeChecker::get_dominant_type(exprected_type, &found_type);
-              self.set_type_for_expression(arg, dominant_type);
-            }
+          let parameters = self.call_parameters(loc, name, args.len())?;
+          for (arg, exprected_type) in args.iter_mut().zip(parameters.iter()) {
+            let found_type = self.resolve_types_expression(arg)?;
+            let dominant_type = self.dominant_type(arg.loc, exprected_type, &found_type)?.clone();
+            self.set_type_for_expression(arg, &dominant_type)?;
           }
-          let Some(func) = self.functions.get(name) else {
-            panic!()
-          };
-          let Some(ref return_type) = func.1 else {
-            panic!()
+          let Some(ref return_type) = self.functions[name].1 else {
+            return Err(self.error(loc, format!("Function '{}' does not return anything!", name)));
           };
           return_type.clone()
         }
@@ -369,27 +743,80 @@ impl TypeChecker {
         ASTNodeType::Return(_) |
         ASTNodeType::FunctionDef(_, _, _, _) |
         ASTNodeType::Assignment(_, _) |
+        ASTNodeType::IndexAssignment(_, _, _) |
+        ASTNodeType::FieldAssignment(_, _) |
         ASTNodeType::BuiltinFunction(_, _) |
         ASTNodeType::Declaration(_, _, _) |
         ASTNodeType::Const(_, _, _) |
+        ASTNodeType::Class(_, _) |
         ASTNodeType::If(_, _, _) |
         ASTNodeType::While(_, _) |
-        ASTNodeType::SExpression(_) => {
-          panic!()
+        ASTNodeType::For(_, _, _, _, _, _) |
+        ASTNodeType::ForEach(_, _, _, _) |
+        ASTNodeType::Break |
+        ASTNodeType::Continue |
+        ASTNodeType::SExpression(_) |
+        ASTNodeType::Error => {
+          return Err(self.error(loc, "Expected an expression, but found a statement"));
         },
     };
-    self.set_type_for_expression(node, &new_type);
-    new_type
+    self.set_type_for_expression(node, &new_type)?;
+    Ok(new_type)
+  }
+
+  // conditions are either bool or an u64 that is compared against 0
+  fn resolve_condition(&mut self, loc: (usize, usize), cond: &mut ASTNode) -> Result<(), Diagnostic> {
+    let found_type = self.resolve_types_expression(cond)?;
+    if found_type != PrimitiveTypes::Bool {
+      let expected_type = TypeChecker::expected_integer(&found_type);
+      let dominant_type = self.dominant_type(loc, &expected_type, &found_type)?.clone();
+      self.set_type_for_expression(cond, &dominant_type)?;
+    }
+    Ok(())
+  }
+
+  fn resolve_comparison(&mut self, loc: (usize, usize), op: &Operator, left: &mut ASTNode, right: &mut ASTNode) -> Result<(), Diagnostic> {
+    let left_t = self.find_operant_type(left)?;
+    let right_t = self.find_operant_type(right)?;
+    let operant_type = self.dominant_type(loc, &left_t, &right_t)?.clone();
+    // comparing only literals, e.g. '1 < 2'
+    let Some(operant_type) = TypeChecker::inferred_type(&operant_type) else {
+      return Err(self.error(loc, format!("Can not compare values of type '{}'", operant_type)));
+    };
+    self.check_comparison(loc, op, &operant_type)?;
+    self.set_type_for_expression(left, &operant_type)?;
+    self.set_type_for_expression(right, &operant_type)
   }
 
-  fn set_type_for_expression(&self, expr: &mut ASTNode, new_type: &PrimitiveTypes) {
+  // All operands of 'a < b < c' share one type, the middle ones are compared on both sides.
+  fn resolve_chained_comparison(&mut self, loc: (usize, usize), operands: &mut [ASTNode], ops: &[Operator]) -> Result<(), Diagnostic> {
+    let mut operant_type = PrimitiveTypes::Void;
+    for operand in operands.iter() {
+      let found_type = self.find_operant_type(operand)?;
+      operant_type = self.dominant_type(operand.loc, &operant_type, &found_type)?.clone();
+    }
+    let Some(operant_type) = TypeChecker::inferred_type(&operant_type) else {
+      return Err(self.error(loc, format!("Can not compare values of type '{}'", operant_type)));
+    };
+    for op in ops {
+      self.check_comparison(loc, op, &operant_type)?;
+    }
+    for operand in operands.iter_mut() {
+      self.set_type_for_expression(operand, &operant_type)?;
+    }
+    Ok(())
+  }
+
+  fn set_type_for_expression(&mut self, expr: &mut ASTNode, new_type: &PrimitiveTypes) -> Result<(), Diagnostic> {
+    let loc = expr.loc;
     match expr.node_type {
-      ASTNodeType::Literal(ref mut typ, _) => {
+      ASTNodeType::Literal(ref mut typ, ref value) => {
+        self.check_literal_range(loc, value, new_type)?;
         let _ = mem::replace(typ, new_type.clone());
       }
-      ASTNodeType::Identifier(_, ref typ) => {
+      ASTNodeType::Identifier(ref name, ref typ) => {
         if typ != new_type {
-          panic!();
+          return Err(self.error(loc, format!("Expected type '{}', but '{}' is of type '{}'", new_type, name, typ)));
         }
       }
       ASTNodeType::BinaryOp(ref mut left, ref op, ref mut right, ref mut typ) => {
@@ -397,57 +824,142 @@ impl TypeChecker {
           Operator::Plus |
           Operator::Minus |
           Operator::Mul |
-          Operator::Div => {
+          Operator::Div |
+          Operator::FloorDiv |
+          Operator::Mod |
+          Operator::Power => {
+            if new_type == &PrimitiveTypes::Str {
+              return Err(self.error(loc, format!("'{:?}' is not defined for strings", op)));
+            }
             let _ = mem::replace(typ, new_type.clone());
-            self.set_type_for_expression(left, new_type);
-            self.set_type_for_expression(right, new_type);
+            self.set_type_for_expression(left, new_type)?;
+            self.set_type_for_expression(right, new_type)?;
+          }
+          Operator::BitAnd |
+          Operator::BitOr |
+          Operator::BitXor |
+          Operator::ShiftLeft |
+          Operator::ShiftRight => {
+            if !matches!(new_type, PrimitiveTypes::Number | PrimitiveTypes::Integer) && !new_type.is_integer() {
+              return Err(self.error(loc, format!("'{:?}' is only defined for integers, but got '{}'", op, new_type)));
+            }
+            let _ = mem::replace(typ, new_type.clone());
+            self.set_type_for_expression(left, new_type)?;
+            self.set_type_for_expression(right, new_type)?;
           }
           Operator::Equal |
+          Operator::NotEqual |
           Operator::Greater |
-          Operator::Less => {
+          Operator::GreaterEqual |
+          Operator::Less |
+          Operator::LessEqual |
+          Operator::Is |
+          Operator::IsNot => {
             if new_type != &PrimitiveTypes::Bool {
-              panic!("Exprected type '{:#?}', but operands '==', '>', '<' are always returning bool", new_type)
+              return Err(self.error(loc, format!("Exprected type '{}', but comparisons are always returning bool", new_type)));
             }
             let _ = mem::replace(typ, PrimitiveTypes::Bool);
-            let left_t = self.find_operant_type(left);
-            let right_t = self.find_operant_type(right);
-            let dominant_type = TypeChecker::get_dominant_type(&left_t, &right_t);
-            self.set_type_for_expression(left, dominant_type);
-            self.set_type_for_expression(right, dominant_type);
+            self.resolve_comparison(loc, op, left, right)?;
           }
 
           Operator::And |
           Operator::Or => {
             if new_type != &PrimitiveTypes::Bool {
-              panic!("The 'and' and 'or' operators can only operate on 'bool' oprants")
+              return Err(self.error(loc, "The 'and' and 'or' operators can only operate on 'bool' oprants"));
             }
             let _ = mem::replace(typ, PrimitiveTypes::Bool);
-            self.set_type_for_expression(left, new_type);
-            self.set_type_for_expression(right, new_type);
+            self.set_type_for_expression(left, new_type)?;
+            self.set_type_for_expression(right, new_type)?;
           }
+          Operator::Not |
+          Operator::BitNot => return Err(self.error(loc, format!("'{:?}' is not a binary operator", op))),
           Operator::Assignment |
-          Operator::ThinArrow => panic!(),
+          Operator::AugmentedAssignment |
+          Operator::ThinArrow => return Err(self.error(loc, format!("'{:?}' can not be used in an expression", op))),
         }
       }
-      ASTNodeType::FunctionCall(ref name, ref mut args, ref mut call_type) => {
-        if args.is_empty() {
-          return;
+      ASTNodeType::UnaryOp(ref op, ref mut operand, ref mut typ) => {
+        match op {
+          Operator::Minus => match new_type {
+            PrimitiveTypes::Number |
+            PrimitiveTypes::Integer |
+            PrimitiveTypes::Float |
+            PrimitiveTypes::F64 |
+            PrimitiveTypes::I64 |
+            PrimitiveTypes::I32 |
+            PrimitiveTypes::I16 |
+            PrimitiveTypes::I8 => {}
+            _ => return Err(self.error(loc, format!("Negation '-' is not defined for type '{}'", new_type))),
+          },
+          Operator::BitNot => match new_type {
+            PrimitiveTypes::Number |
+            PrimitiveTypes::Integer => {}
+            int_type if int_type.is_integer() => {}
+            _ => return Err(self.error(loc, format!("Complement '~' is not defined for type '{}'", new_type))),
+          },
+          Operator::Not => {
+            if new_type != &PrimitiveTypes::Bool {
+              return Err(self.error(loc, "The 'not' operator can only operate on 'bool' oprants"));
+            }
+          }
+          _ => return Err(self.error(loc, format!("'{:?}' is not a unary operator", op))),
         }
-        let Some(parameters) = self.functions.get(name) else {
-          panic!("Could not find function")
-        };
-        let Some(return_type) = &parameters.1 else {
-          panic!("Expected return type for function")
+        let _ = mem::replace(typ, new_type.clone());
+        self.set_type_for_expression(operand, new_type)?;
+      }
+      ASTNodeType::Cast(ref mut value, ref target) => {
+        if target != new_type {
+          return Err(self.error(loc, format!("Expected type '{}', but the cast results in '{}'", new_type, target)));
+        }
+        self.resolve_cast(loc, value, target)?;
+      }
+      ASTNodeType::FString(_) => {
+        return Err(self.error(loc, "f-strings can only be printed directly, like 'print(f\"...\")'"));
+      }
+      ASTNodeType::List(_, _) => {
+        return Err(self.error(loc, "Lists can only initialize arrays, like 'var a: u64[3] = [1, 2, 3]'"));
+      }
+      ASTNodeType::Index(ref array, ref mut index, ref typ) => {
+        if typ != new_type {
+          return Err(self.error(loc, format!("Expected type '{}', but the elements are of type '{}'", new_type, typ)));
+        }
+        self.resolve_index(loc, array, index)?;
+      }
+      ASTNodeType::Field(_, ref field, ref typ, _) => {
+        if typ != new_type {
+          return Err(self.error(loc, format!("Expected type '{}', but the field '{}' is of type '{}'", new_type, field, typ)));
+        }
+      }
+      ASTNodeType::Len(ref mut value) => {
+        if new_type != &PrimitiveTypes::U64 {
+          return Err(self.error(loc, format!("Expected type '{}', but 'len' results in 'u64'", new_type)));
+        }
+        self.resolve_len(loc, value)?;
+      }
+      ASTNodeType::ChainedComparison(ref mut operands, ref ops) => {
+        if new_type != &PrimitiveTypes::Bool {
+          return Err(self.error(loc, format!("Exprected type '{}', but comparisons are always returning bool", new_type)));
+        }
+        self.resolve_chained_comparison(loc, operands, ops)?;
+      }
+      ASTNodeType::Conditional(ref mut cond, ref mut then, ref mut els, ref mut typ) => {
+        self.resolve_condition(loc, cond)?;
+        let _ = mem::replace(typ, new_type.clone());
+        self.set_type_for_expression(then, new_type)?;
+        self.set_type_for_expression(els, new_type)?;
+      }
+      ASTNodeType::FunctionCall(ref name, ref mut args, ref mut call_type) => {
+        let parameters = self.call_parameters(loc, name, args.len())?;
+        let Some(return_type) = self.functions[name].1.clone() else {
+          return Err(self.error(loc, format!("Function '{}' does not return anything!", name)));
         };
-        let parameters = &parameters.0;
-        for (i, arg) in args.iter_mut().enumerate() {
-          let parameter_type = parameters[i].clone();
-          let arg_type = self.find_operant_type(arg);
-          let dominant_type = TypeChecker::get_dominant_type(&parameter_type, &arg_type);
-          self.set_type_for_expression(arg, dominant_type);
+        for (arg, parameter_type) in args.iter_mut().zip(parameters) {
+          let arg_type = self.find_operant_type(arg)?;
+          let dominant_type = self.dominant_type(arg.loc, &parameter_type, &arg_type)?.clone();
+          self.set_type_for_expression(arg, &dominant_type)?;
         }
-        if new_type != return_type {
-          panic!("function call in expression has type '{:?}', but expected type '{:?}'", return_type, new_type)
+        if *new_type != return_type {
+          return Err(self.error(loc, format!("function call in expression has type '{}', but expected type '{}'", return_type, new_type)));
         }
         let _ = replace(call_type, new_type.clone());
       }
@@ -455,147 +967,215 @@ impl TypeChecker {
       ASTNodeType::FunctionDef(_, _, _, _) |
       ASTNodeType::Return(_) |
       ASTNodeType::Assignment(_, _) |
+      ASTNodeType::IndexAssignment(_, _, _) |
+      ASTNodeType::FieldAssignment(_, _) |
       ASTNodeType::BuiltinFunction(_, _) |
       ASTNodeType::Declaration(_, _, _) |
       ASTNodeType::Const(_, _, _) |
+      ASTNodeType::Class(_, _) |
       ASTNodeType::If(_, _, _) |
       ASTNodeType::While(_, _) |
-      ASTNodeType::SExpression(_) => {
-        panic!()
+      ASTNodeType::For(_, _, _, _, _, _) |
+      ASTNodeType::ForEach(_, _, _, _) |
+      ASTNodeType::Break |
+      ASTNodeType::Continue |
+      ASTNodeType::SExpression(_) |
+      ASTNodeType::Error => {
+        return Err(self.error(loc, "Expected an expression, but found a statement"));
       },
     }
+    Ok(())
   }
 
-  fn get_dominant_type<'a>(left_t: &'a PrimitiveTypes, right_t: &'a PrimitiveTypes) -> &'a PrimitiveTypes {
-    match left_t {
-      PrimitiveTypes::Number => {
-        match right_t {
-          PrimitiveTypes::Void |
-          PrimitiveTypes::Number => left_t,
-
-          PrimitiveTypes::Float |
-          PrimitiveTypes::F64 |
-          PrimitiveTypes::Integer |
-          PrimitiveTypes::U64 => right_t,
-
-          PrimitiveTypes::Bool => panic!("Mismatch of types '{:#?}' and 'bool", left_t),
-          PrimitiveTypes::COUNT => panic!("Count is not a valid type"),
-        }
-      },
-
-      PrimitiveTypes::Float => {
-        match right_t {
-          PrimitiveTypes::Void |
-          PrimitiveTypes::Number |
-          PrimitiveTypes::Float => left_t,
-
-          PrimitiveTypes::F64 => right_t,
-
-          PrimitiveTypes::Integer |
-          PrimitiveTypes::U64 => panic!("mismatch in types {:#?} {:#?}", left_t, right_t),
-
-          PrimitiveTypes::Bool => panic!("Mismatch of types '{:#?}' and 'bool", left_t),
-          PrimitiveTypes::COUNT => panic!("Count is not a valid type"),
-        }
-      },
-
-      PrimitiveTypes::Integer => {
-        match right_t {
-          PrimitiveTypes::Void |
-          PrimitiveTypes::Number |
-          PrimitiveTypes::Integer => left_t,
-
-          PrimitiveTypes::U64 => right_t,
+  // The type a value without an explicit type ends up with: integer literals are 'u64'
+  // and float literals 'f64'.
+  fn inferred_type(found_type: &PrimitiveTypes) -> Option<PrimitiveTypes> {
+    match found_type {
+      PrimitiveTypes::Number |
+      PrimitiveTypes::Integer => Some(PrimitiveTypes::U64),
+      PrimitiveTypes::Float => Some(PrimitiveTypes::F64),
+      PrimitiveTypes::Array(element, len) => Some(PrimitiveTypes::Array(Box::new(TypeChecker::inferred_type(element)?), *len)),
+      PrimitiveTypes::Void |
+      PrimitiveTypes::COUNT => None,
+      typ => Some(typ.clone()),
+    }
+  }
 
-          PrimitiveTypes::Float |
-          PrimitiveTypes::F64 => panic!("mismatch in types {:#?} {:#?}", left_t, right_t),
+  // The typed prints want their own type, 'print' takes any value and decides per argument.
+  fn resolve_builtin_argument(&mut self, loc: (usize, usize), name: &str, expr: &mut ASTNode) -> Result<(), Diagnostic> {
+    if let ASTNodeType::FString(ref mut parts) = expr.node_type {
+      if name != "print" && name != "print_str" {
+        return Err(self.error(expr.loc, format!("'{}' can not print an f-string", name)));
+      }
+      return self.resolve_interpolations(parts);
+    }
+    if name == "print" {
+      return self.resolve_printed_value(expr).map(|_| ());
+    }
+    let found_type = self.resolve_types_expression(expr)?;
+    let expected_type = match name {
+      "print_int" => TypeChecker::expected_integer(&found_type),
+      "print_float" => PrimitiveTypes::F64,
+      "print_bool" => PrimitiveTypes::Bool,
+      "print_str" => PrimitiveTypes::Str,
+      _ => return Err(self.error(loc, format!("Unknown builtin function '{}'", name))),
+    };
+    let dominant_type = self.dominant_type(expr.loc, &expected_type, &found_type)?;
+    self.set_type_for_expression(expr, dominant_type)
+  }
 
-          PrimitiveTypes::Bool => panic!("Mismatch of types '{:#?}' and 'bool", left_t),
-          PrimitiveTypes::COUNT => panic!("Count is not a valid type"),
-        }
-      },
+  // Values without an explicit type are printed with their default type
+  fn resolve_printed_value(&mut self, expr: &mut ASTNode) -> Result<PrimitiveTypes, Diagnostic> {
+    let found_type = self.resolve_types_expression(expr)?;
+    let Some(typ) = TypeChecker::inferred_type(&found_type) else {
+      return Err(self.error(expr.loc, format!("Can not print a value of type '{}'", found_type)));
+    };
+    self.set_type_for_expression(expr, &typ)?;
+    Ok(typ)
+  }
 
-      PrimitiveTypes::Void => {
-        match right_t {
-          PrimitiveTypes::Void |
-          PrimitiveTypes::Number |
-          PrimitiveTypes::Integer |
-          PrimitiveTypes::U64 |
-          PrimitiveTypes::Float |
-          PrimitiveTypes::F64 |
-          PrimitiveTypes::Bool => right_t,
+  fn resolve_interpolations(&mut self, parts: &mut [Interpolation]) -> Result<(), Diagnostic> {
+    for part in parts {
+      let Interpolation::Value(ref mut value, ref spec) = part else {
+        continue;
+      };
+      let typ = self.resolve_printed_value(value)?;
+      if spec.is_some() && typ != PrimitiveTypes::F64 {
+        return Err(self.error(value.loc, format!("A format spec is only defined for floats, but got '{}'", typ)));
+      }
+    }
+    Ok(())
+  }
 
-          PrimitiveTypes::COUNT => panic!("Count is not a valid type"),
-        }
-      },
+  // Integer expressions keep their type. Ambiguous ones (literals) fall back to 'u64'.
+  fn expected_integer(found_type: &PrimitiveTypes) -> PrimitiveTypes {
+    if found_type.is_integer() {
+      found_type.clone()
+    } else {
+      PrimitiveTypes::U64
+    }
+  }
 
-      PrimitiveTypes::U64 => {
-        match right_t {
-          PrimitiveTypes::Void |
-          PrimitiveTypes::Number |
-          PrimitiveTypes::Integer |
-          PrimitiveTypes::U64 => left_t,
+  fn check_literal_range(&self, loc: (usize, usize), value: &str, value_type: &PrimitiveTypes) -> Result<(), Diagnostic> {
+    let Some((min, max)) = value_type.integer_range() else {
+      return Ok(());
+    };
+    match value.parse::<i128>() {
+      Ok(n) if n >= min && n <= max => Ok(()),
+      _ => Err(self.error(loc, format!("Literal '{}' does not fit into '{}' ({} to {})", value, value_type, min, max))),
+    }
+  }
 
-          PrimitiveTypes::F64 |
-          PrimitiveTypes::Float => panic!("mismatch in types {:#?} {:#?}", left_t, right_t),
+  // bools can only be compared for (in)equality and identity. There are no references yet,
+  // so 'is' is only defined for bools.
+  fn check_comparison(&self, loc: (usize, usize), op: &Operator, operant_type: &PrimitiveTypes) -> Result<(), Diagnostic> {
+    let is_identity = matches!(op, Operator::Is | Operator::IsNot);
+    if operant_type == &PrimitiveTypes::Bool && !is_identity && !matches!(op, Operator::Equal | Operator::NotEqual) {
+      return Err(self.error(loc, format!("'{:?}' is not defined for 'bool' operants", op)));
+    }
+    if operant_type == &PrimitiveTypes::Str {
+      return Err(self.error(loc, format!("'{:?}' is not defined for 'str' operants", op)));
+    }
+    if operant_type != &PrimitiveTypes::Bool && is_identity {
+      return Err(self.error(loc, format!("'{:?}' is only defined for 'bool' operants, but got '{}'", op, operant_type)));
+    }
+    Ok(())
+  }
 
-          PrimitiveTypes::Bool => panic!("Mismatch of types '{:#?}' and 'bool", left_t),
-          PrimitiveTypes::COUNT => panic!("Count is not a valid type"),
-        }
-      },
-      PrimitiveTypes::F64 => {
-        match right_t {
-          PrimitiveTypes::Void |
-          PrimitiveTypes::Number |
-          PrimitiveTypes::Float |
-          PrimitiveTypes::F64 => left_t,
+  fn dominant_type<'a>(&self, loc: (usize, usize), left_t: &'a PrimitiveTypes, right_t: &'a PrimitiveTypes) -> Result<&'a PrimitiveTypes, Diagnostic> {
+    TypeChecker::get_dominant_type(left_t, right_t).map_err(|msg| self.error(loc, msg))
+  }
 
-          PrimitiveTypes::Integer |
-          PrimitiveTypes::U64 => panic!("mismatch in types {:#?} {:#?}", left_t, right_t),
+  fn get_dominant_type<'a>(left_t: &'a PrimitiveTypes, right_t: &'a PrimitiveTypes) -> Result<&'a PrimitiveTypes, String> {
+    let mismatch = || Err(format!("Mismatch of types '{}' and '{}'", left_t, right_t));
+    match (left_t, right_t) {
+      (PrimitiveTypes::COUNT, _) |
+      (_, PrimitiveTypes::COUNT) => Err("Count is not a valid type".to_string()),
 
-          PrimitiveTypes::Bool => panic!("Mismatch of types '{:#?}' and 'bool", left_t),
-          PrimitiveTypes::COUNT => panic!("Count is not a valid type"),
-        }
-      },
-      PrimitiveTypes::Bool => {
-        match right_t {
-          PrimitiveTypes::Bool => left_t,
+      (PrimitiveTypes::Bool, PrimitiveTypes::Bool) => Ok(left_t),
+      (PrimitiveTypes::Bool, _) => mismatch(),
+      (PrimitiveTypes::Void, _) => Ok(right_t),
+      (_, PrimitiveTypes::Void) => Ok(left_t),
+      (_, PrimitiveTypes::Bool) => mismatch(),
 
-          PrimitiveTypes::Number |
-          PrimitiveTypes::Float |
-          PrimitiveTypes::Integer |
-          PrimitiveTypes::Void |
-          PrimitiveTypes::U64 |
-          PrimitiveTypes::F64 => panic!("Mismatch of types 'bool' and '{:#?}", right_t),
+      // the more explicit type wins: Number -> Integer/Float -> u64/f64/...
+      (PrimitiveTypes::Number, _) => Ok(right_t),
+      (_, PrimitiveTypes::Number) => Ok(left_t),
+      (PrimitiveTypes::Integer, int_type) if int_type.is_integer() || int_type == &PrimitiveTypes::Integer => Ok(right_t),
+      (int_type, PrimitiveTypes::Integer) if int_type.is_integer() => Ok(left_t),
+      (PrimitiveTypes::Float, PrimitiveTypes::Float | PrimitiveTypes::F64) => Ok(right_t),
+      (PrimitiveTypes::F64, PrimitiveTypes::Float) => Ok(left_t),
 
-          PrimitiveTypes::COUNT => panic!("Count is not a valid type"),
-        }
-      }
-      PrimitiveTypes::COUNT => panic!("Count is not a valid type"),
+      (left, right) if left == right => Ok(left_t),
+      _ => mismatch(),
     }
+  }
 
+  // The parameter types of 'name', once the call passes as many arguments as it has parameters
+  fn call_parameters(&self, loc: (usize, usize), name: &str, arg_count: usize) -> Result<Vec<PrimitiveTypes>, Diagnostic> {
+    let Some((parameters, _)) = self.functions.get(name) else {
+      return Err(self.error(loc, format!("Function '{}' was not defined", name)));
+    };
+    if arg_count != parameters.len() {
+      let plural = if parameters.len() == 1 { "" } else { "s" };
+      return Err(self.error(loc, format!("'{}' takes {} argument{}, but got {}", name, parameters.len(), plural, arg_count)));
+    }
+    Ok(parameters.clone())
   }
 
-  fn find_operant_type(&self, expr: &ASTNode) -> PrimitiveTypes {
+  fn find_operant_type(&self, expr: &ASTNode) -> Result<PrimitiveTypes, Diagnostic> {
     match expr.node_type {
-        ASTNodeType::Identifier(_, ref typ) => typ.clone(),
-        ASTNodeType::Literal(ref typ, _) => typ.clone(),
+        ASTNodeType::Identifier(_, ref typ) => Ok(typ.clone()),
+        ASTNodeType::Literal(ref typ, _) => Ok(typ.clone()),
         ASTNodeType::FunctionCall(ref name, _, _) => {
-          let Some(funciton) = self.functions.get(name) else {
-            panic!("Could not find function '{}' while type checking function call", name);
+          let Some(function) = self.functions.get(name) else {
+            return Err(self.error(expr.loc, format!("Could not find function '{}' while type checking function call", name)));
           };
-          let Some(ref return_type) = funciton.1 else {
-            panic!("Function '{}' does not return anything!", name)
+          let Some(ref return_type) = function.1 else {
+            return Err(self.error(expr.loc, format!("Function '{}' does not return anything!", name)));
           };
-          return_type.clone()
+          Ok(return_type.clone())
         }
-        ASTNodeType::BinaryOp(ref left, _, ref right, _) => {
-          let left_t = self.find_operant_type(left);
-          let right_t = self.find_operant_type(right);
-          TypeChecker::get_dominant_type(&left_t, &right_t).clone()
+        ASTNodeType::BinaryOp(ref left, ref op, ref right, _) => {
+          let left_t = self.find_operant_type(left)?;
+          let right_t = self.find_operant_type(right)?;
+          let dominant_type = self.dominant_type(expr.loc, &left_t, &right_t)?.clone();
+          match op {
+            Operator::Equal |
+            Operator::NotEqual |
+            Operator::Greater |
+            Operator::GreaterEqual |
+            Operator::Less |
+            Operator::LessEqual |
+            Operator::Is |
+            Operator::IsNot => Ok(PrimitiveTypes::Bool),
+            _ => Ok(dominant_type),
+          }
         },
+        ASTNodeType::UnaryOp(Operator::Not, _, _) => Ok(PrimitiveTypes::Bool),
+        ASTNodeType::Cast(_, ref target) => Ok(target.clone()),
+        ASTNodeType::Len(_) => Ok(PrimitiveTypes::U64),
+        ASTNodeType::FString(_) => Ok(PrimitiveTypes::Str),
+        ASTNodeType::Index(_, _, ref typ) => Ok(typ.clone()),
+        ASTNodeType::Field(_, _, ref typ, _) => Ok(typ.clone()),
+        // the elements decide the type, like the operants of an operation
+        ASTNodeType::List(ref elements, _) => {
+          let mut element_type = PrimitiveTypes::Void;
+          for element in elements {
+            let found_type = self.find_operant_type(element)?;
+            element_type = self.dominant_type(element.loc, &element_type, &found_type)?.clone();
+          }
+          Ok(PrimitiveTypes::Array(Box::new(element_type), elements.len()))
+        }
+        ASTNodeType::ChainedComparison(_, _) => Ok(PrimitiveTypes::Bool),
+        ASTNodeType::Conditional(_, ref then, ref els, _) => {
+          let then_t = self.find_operant_type(then)?;
+          let els_t = self.find_operant_type(els)?;
+          Ok(self.dominant_type(expr.loc, &then_t, &els_t)?.clone())
+        }
+        ASTNodeType::UnaryOp(_, ref operand, _) => self.find_operant_type(operand),
 
-        _ => panic!("Error while evaluating expr type. Unexpected ASTNode")
+        _ => Err(self.error(expr.loc, "Error while evaluating expr type. Unexpected ASTNode")),
     }
   }
 
@@ -1,15 +1,22 @@
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::{self, Command};
 use std::{env, fs, io};
 use std::time::Instant;
 
 use blythia::builder::Builder;
 use blythia::compiler::Compiler;
+use blythia::diagnostic::Diagnostic;
 use blythia::lexer::Lexer;
 use blythia::parser::Parser;
 use blythia::type_checker::TypeChecker;
 
+fn report(diagnostics: &[Diagnostic], source: &str) -> ! {
+    for diagnostic in diagnostics {
+        eprintln!("{}", diagnostic.render(source));
+    }
+    process::exit(1);
+}
 
 fn test2(file: &Path) {
     let f = fs::read_to_string(file);
@@ -25,21 +32,29 @@ fn test2(file: &Path) {
 
     println!("Meassuring parser time");
     let now = Instant::now();
-    let mut ast = parser.parse();
+    let mut ast = match parser.parse() {
+        Ok(ast) => ast,
+        Err(diagnostics) => report(&diagnostics, &content),
+    };
     let elapsed = now.elapsed();
     println!("Elapsed: {:.2?}", elapsed);
 
     println!("Meassuring type checking time");
     let now = Instant::now();
-    let mut type_checker = TypeChecker::new();
-    type_checker.prepare_ast(&mut ast);
+    let mut type_checker = TypeChecker::new(file_name.clone());
+    if let Err(diagnostic) = type_checker.prepare_ast(&mut ast) {
+        report(&[diagnostic], &content);
+    }
     let elapsed = now.elapsed();
     println!("Elapsed: {:.2?}", elapsed);
 
     println!("Meassuring build program time");
     let mut op = Builder::new(file_name.clone());
     let now = Instant::now();
-    let program = op.build_program(&mut ast);
+    let program = match op.build_program(&mut ast) {
+        Ok(program) => program,
+        Err(diagnostic) => report(&[diagnostic], &content),
+    };
     let elapsed = now.elapsed();
     println!("Elapsed: {:.2?}", elapsed);
 
@@ -0,0 +1,20 @@
+# Python style declarations without 'var': 5 -3 2 7
+x: u64 = 5
+print_int(x)
+y: i32 = -3
+print_int(y)
+z: f64 = 2.5
+print_int(u64(z))
+
+def add(a: u64, b: u64) -> u64:
+  total: u64 = a + b
+  return total
+
+print_int(add(x, 2))
+
+# declared without a value and shadowed: 0 9
+n: u64
+n = 0
+print_int(n)
+n: u8 = 9
+print_int(n)
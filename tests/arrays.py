@@ -0,0 +1,38 @@
+# fixed-size arrays, every index is checked at runtime
+var a: u64[5]
+var b: i8[3]
+var f: f64[4]
+var flags: bool[2]
+var i: u64 = 0
+while i < 5:
+  a[i] = i * i
+  i += 1
+print(a[0], a[1], a[4])
+b[0] = -5
+b[1] = 100
+b[1] += 100
+print(b[0], b[1], b[2])
+f[3] = 2.5
+f[2] = f[3] * 2.0
+print(f[2], f[3], f[0])
+flags[1] = true
+print(flags[0], flags[1])
+
+def sum_local() -> u64:
+  var c: u64[4]
+  var total: u64 = 0
+  var j: u64 = 0
+  c[2] = 7
+  c[3] = 8
+  while j < 4:
+    total += c[j]
+    j += 1
+  return total
+
+print(sum_local())
+var k: i64 = 2
+print(a[k + 1])
+print(f"{a[2]} {f[3]:.2f}")
+# IndexError: array index out of range, exits with status 1
+a[5] = 1
+print("unreachable")
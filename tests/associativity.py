@@ -0,0 +1,6 @@
+# 5 1 20 10
+print_int(10 - 3 - 2)
+print_int(8 / 4 / 2)
+print_int(100 / 10 * 2)
+var x: u64 = 20
+print_int(x - 2 * 3 - 4)
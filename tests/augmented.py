@@ -0,0 +1,40 @@
+# every augmented assignment: 13 10 30 15 7 1 1 1 7 4 32 8
+var x: i64 = 10
+x += 3
+print_int(x)
+x -= 3
+print_int(x)
+x *= 3
+print_int(x)
+x /= 2
+print_int(x)
+x //= 2
+print_int(x)
+x %= 3
+print_int(x)
+x **= 3
+print_int(x)
+x &= 7
+print_int(x)
+x |= 6
+print_int(x)
+x ^= 3
+print_int(x)
+x <<= 3
+print_int(x)
+x >>= 2
+print_int(x)
+
+# the right side is a whole expression: 16 5
+x += x * 1
+print_int(x)
+var f: f64 = 1.0
+f += 1.5
+f *= 2.0
+print_int(i64(f))
+
+# loop counters: 3 2 1
+var i: u8 = 3
+while i > 0:
+  print_int(i)
+  i -= 1
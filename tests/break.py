@@ -0,0 +1,27 @@
+# prints 1 to 7, but skips 3 and 5
+var i: u64 = 0
+while i < 10:
+  i = i + 1
+  if i == 8:
+    break
+  if i == 3:
+    continue
+  if i == 5:
+    continue
+  print_int(i)
+
+# break only leaves the innermost loop
+def count_pairs(n: u64) -> u64:
+  var pairs: u64 = 0
+  var a: u64 = 0
+  while a < n:
+    a = a + 1
+    var b: u64 = 0
+    while b < n:
+      b = b + 1
+      if b > a:
+        break
+      pairs = pairs + 1
+  return pairs
+
+print_int(count_pairs(4))
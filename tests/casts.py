@@ -0,0 +1,35 @@
+# f64 to int truncates towards zero: 3 -3 7
+var x: f64 = 3.75
+print_int(i64(x))
+print_int(i64(-x))
+print_int(u64(f64(7)))
+
+# int to f64 and back: 20 4
+var n: u64 = 10
+var half: f64 = f64(n) / 4.0
+print_int(u64(half * 8.0))
+var m: i32 = -9
+print_int(i64(f64(m) / -2.0))
+
+# unsigned values above 2^63 survive the trip through f64: 18446744073709549568
+var big: u64 = 18446744073709549568
+print_int(u64(f64(big)))
+
+# to bool: 0 1 1 0 1
+print_int(u64(bool(0)))
+print_int(u64(bool(n)))
+print_int(u64(bool(-0.5)))
+print_int(u64(bool(0.0)))
+var t: bool = true
+print_int(u8(t))
+
+# bool to f64: 2
+print_int(i64(f64(t) + f64(true)))
+
+# between int widths: 255 -1 44 4294967295 -56
+var w: u64 = 300
+print_int(u8(w) - u8(45))
+print_int(i8(u8(255)))
+print_int(u8(w))
+print_int(u32(i8(-1)))
+print_int(i8(u8(200)))
@@ -0,0 +1,22 @@
+def middle(n: i64) -> i64:
+  print_int(n)
+  return n
+
+# a < b < c: 1 0 0 1 1
+var x: i64 = 5
+print_int(u64(0 < x < 10))
+print_int(u64(0 < x < 5))
+print_int(u64(10 < x < 20))
+print_int(u64(1 <= x <= 5 != 6))
+print_int(u64(-3 < -2 < -1 < 0 < x))
+
+# the middle operand is evaluated once, later links are skipped: 5 1 5 0 7 0
+print_int(u64(0 < middle(5) < 10))
+print_int(u64(10 < middle(5) < middle(99)))
+print_int(u64(0 < middle(7) > 8 < middle(99)))
+
+# parentheses still compare a bool: 1 5
+var t: bool = true
+print_int(u64((1 < x) == t == true))
+if 0 <= x < 10 and not 5 < x < 7:
+  print_int(x)
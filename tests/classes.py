@@ -0,0 +1,36 @@
+# classes group typed fields, every field is aligned to its own size
+class Point:
+  x: f64
+  y: f64
+
+class Pixel:
+  on: bool
+  x: u16
+  y: u16
+  color: u32
+  name: str
+
+var p: Point
+p.x = 1.5
+p.y = p.x * 2.0
+p.x += 0.25
+print(p.x, p.y)
+
+var px: Pixel
+px.x = 65535
+px.x += 1
+px.y = 7
+px.color = 16777215
+px.on = true
+px.name = "dot"
+print(px.on, px.x, px.y, px.color, px.name, len(px.name))
+print(f"({p.x}, {p.y:.1f})")
+
+def dist2(a: f64, b: f64) -> f64:
+  var q: Point
+  q.x = a
+  q.y = b
+  return q.x * q.x + q.y * q.y
+
+
+print(dist2(3.0, 4.0))
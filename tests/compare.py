@@ -0,0 +1,57 @@
+def show(b: bool) -> void:
+  if b:
+    print_int(1)
+  else:
+    print_int(0)
+
+# u64: 1 0 0 1 1 0, then 0 1 0 1 1 0 for equal values
+var a: u64 = 3
+var b: u64 = 7
+show(a < b)
+show(a > b)
+show(a == b)
+show(a != b)
+show(a <= b)
+show(a >= b)
+b = 3
+show(a < b)
+show(a <= b)
+show(a > b)
+show(a >= b)
+show(a == b)
+show(a != b)
+
+# f64: 1 0 0 1 1 0, then 0 1 0 1 1 0 for equal values
+var x: f64 = 1.5
+var y: f64 = 2.25
+show(x < y)
+show(x > y)
+show(x == y)
+show(x != y)
+show(x <= y)
+show(x >= y)
+y = 1.5
+show(x < y)
+show(x <= y)
+show(x > y)
+show(x >= y)
+show(x == y)
+show(x != y)
+
+# NaN is unordered, only != is true: 0 0 0 0 0 1
+var zero: f64 = 0.0
+var nan: f64 = zero / zero
+show(nan < x)
+show(nan > x)
+show(nan <= x)
+show(nan >= x)
+show(nan == nan)
+show(nan != nan)
+
+# bool: 1 0 0 1
+var t: bool = true
+var f: bool = false
+show(t == true)
+show(t == f)
+show(f != false)
+show(t != f)
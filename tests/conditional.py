@@ -0,0 +1,27 @@
+def check(n: u64, result: u64) -> u64:
+  print_int(n)
+  return result
+
+# picks a branch: 1 2 7
+var x: u64 = 5
+print_int(1 if x > 3 else 2)
+print_int(1 if x > 5 else 2)
+print_int(x + (2 if x == 5 else 0))
+
+# chained and nested: 20 -1 3
+var y = 10 if x < 2 else 20 if x < 8 else 30
+print_int(y)
+var s: i32 = -1 if x else 1
+print_int(s)
+print_int(u64((1.5 if x > 0 else 2.5) * 2.0))
+
+# only the chosen branch runs: 11 100 12 200
+var z = check(11, 100) if x > 0 else check(99, 0)
+print_int(z)
+z = check(99, 0) if x == 0 else check(12, 200)
+print_int(z)
+
+# bools: 1 0
+var b = x > 1 if x else false
+print_int(u64(b))
+print_int(u64(not b if true else b))
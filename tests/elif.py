@@ -0,0 +1,32 @@
+def classify(x: u64) -> u64:
+  if x < 10:
+    return 1
+  elif x < 100:
+    return 2
+  elif x < 1000:
+    return 3
+  else:
+    return 4
+
+print_int(classify(5))
+print_int(classify(50))
+print_int(classify(500))
+print_int(classify(5000))
+
+var x: u64 = 7
+if x == 1:
+  print_int(1)
+elif x == 7:
+  print_int(7)
+  if x > 5:
+    print_int(5)
+  elif x > 3:
+    print_int(3)
+elif x > 0:
+  print_int(0)
+
+if x == 8:
+  print_int(8)
+elif x == 9:
+  print_int(9)
+print_int(x)
@@ -0,0 +1,9 @@
+var a: u64[]
+var b: u64[0]
+var c: u64[4
+var d: u64[4]
+print(d[1)
+d[1]
+d[next()] += 1
+d[1 + len(name())] *= 2
+d[next()] = d[next()] + 1
@@ -0,0 +1,4 @@
+var x = 1
+x +=
+x <<= 1
+x *= 2 3
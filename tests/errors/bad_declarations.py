@@ -0,0 +1,6 @@
+var x u64 = 1
+var y: u65 = 2
+var : u64 = 3
+var z: u64 = 4
+print_int(z)
+w: u65 = 1
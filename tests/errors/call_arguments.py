@@ -0,0 +1,5 @@
+def f(a: u64) -> u64:
+    return a
+
+if f(1, 2) == 1:
+    print(1)
@@ -0,0 +1,4 @@
+def f(a: u64) -> u64:
+    return a
+
+f()
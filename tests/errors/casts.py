@@ -0,0 +1,3 @@
+var x: f64 = f64(1, 2)
+var y: u64 = u64()
+print_int(u8(3))
@@ -0,0 +1,11 @@
+class:
+  x: u64
+class Point
+  x: u64
+class Vec:
+  x: f64
+  print(1)
+var v: Vec
+v. = 1.0
+class Vec:
+  y: f64
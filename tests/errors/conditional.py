@@ -0,0 +1,3 @@
+var x = 1
+var y = 2 if x > 0
+var z = 2 if x > 0 else
@@ -0,0 +1,8 @@
+for i range(3):
+  print_int(i)
+for i in 3:
+  print_int(i)
+for i in range(1, 2, 3, 4):
+  print_int(i)
+for i in range(3)
+  print_int(i)
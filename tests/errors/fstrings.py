@@ -0,0 +1,8 @@
+var x = 1
+print(f"{x")
+print(f"{}")
+print(f"a } b")
+print(f"{x:>5}")
+print(f"{x +}")
+print(f"{x:.20f}")
+print(f"{x} {'a'}")
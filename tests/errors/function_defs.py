@@ -0,0 +1,7 @@
+def f(a u64) -> void:
+  print_int(a)
+def g(a: u64) void:
+  print_int(a)
+def h(a: u64) -> void:
+  print_int(a
+h(1)
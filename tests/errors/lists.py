@@ -0,0 +1,5 @@
+var a = []
+var b: u64[2] = [1 2]
+var c: u64[2] = [1, 2
+for x in b
+  print(x)
@@ -0,0 +1,7 @@
+var x: u64 = 10
+while x > 0
+  print_int(x)
+  x = x - 1
+if x == 0
+  print_int(x)
+print_int(x)
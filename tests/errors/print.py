@@ -0,0 +1,4 @@
+print_int(1, 2)
+print_float()
+print(1, 2.5, true)
+print_bool(true, false)
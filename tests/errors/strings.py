@@ -0,0 +1,4 @@
+var a: str = "never closed
+var b: str = "bad \q escape"
+print_str(len("x"), "y")
+var c = len()
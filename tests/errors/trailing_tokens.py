@@ -0,0 +1,5 @@
+var x: u64 = 1 2
+x = 3 4
+print_int(x) x
+else:
+  print_int(x)
@@ -0,0 +1,7 @@
+var x: u64 = 1
+    print_int(x)
+def f(a: u64) -> void:
+  print_int(a)
+      print_int(a)
+  print_int(a)
+f(x)
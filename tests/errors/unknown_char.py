@@ -0,0 +1,4 @@
+var x: u64 = 1 $ 2
+var y: u64 = 3
+y = y @ 4
+print_int(y)
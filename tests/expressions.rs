@@ -0,0 +1,185 @@
+use blythia::ast::{ASTNode, ASTNodeType};
+use blythia::lexer::Lexer;
+use blythia::parser::Parser;
+use blythia::token::Operator;
+
+// Evaluates a constant integer expression straight from the AST, so the tree shape
+// decides the result.
+fn eval(node: &ASTNode) -> i64 {
+    match &node.node_type {
+        ASTNodeType::Literal(_, value) => value.parse().unwrap(),
+        ASTNodeType::BinaryOp(left, op, right, _) => eval_binary(op, eval(left), eval(right)),
+        ASTNodeType::UnaryOp(op, operand, _) => {
+            let value = eval(operand);
+            match op {
+                Operator::Minus => -value,
+                Operator::BitNot => !value,
+                Operator::Not => (value == 0) as i64,
+                op => panic!("can not evaluate {:?}", op),
+            }
+        }
+        ASTNodeType::ChainedComparison(operands, ops) => {
+            let values: Vec<i64> = operands.iter().map(eval).collect();
+            let links = ops.iter().zip(values.windows(2));
+            links.map(|(op, pair)| eval_binary(op, pair[0], pair[1])).all(|holds| holds != 0) as i64
+        }
+        ASTNodeType::Conditional(cond, then, els, _) => {
+            if eval(cond) != 0 { eval(then) } else { eval(els) }
+        }
+        other => panic!("can not evaluate {:?}", other),
+    }
+}
+
+// Rounds toward negative infinity like Python
+fn floor_div(l: i64, r: i64) -> i64 {
+    let q = l / r;
+    if l % r != 0 && (l < 0) != (r < 0) { q - 1 } else { q }
+}
+
+fn eval_binary(op: &Operator, l: i64, r: i64) -> i64 {
+    match op {
+        Operator::Plus => l + r,
+        Operator::Minus => l - r,
+        Operator::Mul => l * r,
+        Operator::Div => l / r,
+        Operator::FloorDiv => floor_div(l, r),
+        Operator::Mod => l - r * floor_div(l, r),
+        Operator::Power => l.pow(r as u32),
+        Operator::BitAnd => l & r,
+        Operator::BitOr => l | r,
+        Operator::BitXor => l ^ r,
+        Operator::ShiftLeft => l << r,
+        Operator::ShiftRight => l >> r,
+        Operator::Equal => (l == r) as i64,
+        Operator::Greater => (l > r) as i64,
+        Operator::NotEqual => (l != r) as i64,
+        Operator::Less => (l < r) as i64,
+        Operator::LessEqual => (l <= r) as i64,
+        Operator::GreaterEqual => (l >= r) as i64,
+        Operator::Is => (l == r) as i64,
+        Operator::IsNot => (l != r) as i64,
+        Operator::And => (l != 0 && r != 0) as i64,
+        Operator::Or => (l != 0 || r != 0) as i64,
+        op => panic!("can not evaluate {:?}", op),
+    }
+}
+
+fn eval_source(expr: &str) -> i64 {
+    let source = format!("print_int({expr})\n");
+    let mut parser = Parser::new(Lexer::new(&source, "expr.py".to_string()), "expr.py".to_string());
+    let ast = parser.parse().unwrap();
+    let ASTNodeType::BuiltinFunction(_, ref args) = ast[0].node_type else {
+        panic!("expected print_int, got {:?}", ast[0]);
+    };
+    eval(&args[0])
+}
+
+#[test]
+fn binary_operators_are_left_associative() {
+    let cases = [
+        ("10 - 3 - 2", 5),
+        ("8 / 4 / 2", 1),
+        ("100 / 10 * 2", 20),
+        ("1 - 2 + 3", 2),
+        ("20 - 2 * 3 - 4", 10),
+        ("2 * 3 + 4 * 5 - 6 / 2", 23),
+        ("10 - (3 - 2)", 9),
+        ("1 + 2 == 3", 1),
+        ("(1 < 2) == 1", 1),
+        ("3 <= 3", 1),
+        ("2 >= 3", 0),
+        ("1 != 2", 1),
+        ("2 * 2 >= 1 + 3 != 0", 1),
+        ("1 - 2 <= -1", 1),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_source(expr), expected, "wrong result for '{}'", expr);
+    }
+}
+
+#[test]
+fn unary_operators() {
+    let cases = [
+        ("-3 + 5", 2),
+        ("2 * -3", -6),
+        ("-2 - -3", 1),
+        ("--4", 4),
+        ("~0", -1),
+        ("-(1 + 2) * 2", -6),
+        ("not 1 == 2", 1),
+        ("not (1 < 2) == 1", 0),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_source(expr), expected, "wrong result for '{}'", expr);
+    }
+}
+
+#[test]
+fn keyword_operators() {
+    let cases = [
+        ("1 == 1 or 1 == 2 and 1 == 2", 1),
+        ("1 == 1 || 1 == 2 && 1 == 2", 1),
+        ("(1 == 1 or 1 == 2) and 1 == 2", 0),
+        ("not 1 == 1 or 1 == 1", 1),
+        ("not 1 == 2 and 1 == 2", 0),
+        ("(1 == 1) is (1 == 1)", 1),
+        ("(1 == 1) is not (1 == 2)", 1),
+        ("(1 == 2) is not (1 == 2) or 1 == 1", 1),
+        ("not (1 == 2) is (1 == 2)", 0),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_source(expr), expected, "wrong result for '{}'", expr);
+    }
+}
+
+#[test]
+fn arithmetic_and_bitwise_precedence() {
+    let cases = [
+        ("2 ** 3 ** 2", 512),
+        ("-2 ** 2", -4),
+        ("2 * 3 ** 2", 18),
+        ("7 // 2 * 2 % 5", 1),
+        ("-7 // 2 % 3", 2),
+        ("9007199254740993 // 1 % 10", 3),
+        ("1 + 2 << 3", 24),
+        ("1 << 3 + 1", 16),
+        ("6 & 3 | 8", 10),
+        ("1 | 6 ^ 3 & 5", 7),
+        ("1 == 1 & 1", 1),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_source(expr), expected, "wrong result for '{}'", expr);
+    }
+}
+
+#[test]
+fn conditional_expressions() {
+    let cases = [
+        ("1 if 1 == 1 else 2", 1),
+        ("1 if 1 == 2 else 2 + 3", 5),
+        ("1 + 2 if 1 == 2 else 4", 4),
+        ("1 if 1 == 2 else 2 if 1 == 1 else 3", 2),
+        ("(1 if 1 == 2 else 2) * 3", 6),
+        ("1 if 1 == 1 or 1 == 2 else 0", 1),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_source(expr), expected, "wrong result for '{}'", expr);
+    }
+}
+
+#[test]
+fn chained_comparisons() {
+    let cases = [
+        ("1 < 2 < 3", 1),
+        ("1 < 3 < 2", 0),
+        ("3 > 2 > 1 >= 1", 1),
+        ("1 < 2 == 2", 1),
+        ("1 < 2 == 1", 0),
+        ("(1 < 2) == 1", 1),
+        ("not 1 < 2 < 3", 0),
+        ("1 == 1 < 2 and 2 > 1", 1),
+    ];
+    for (expr, expected) in cases {
+        assert_eq!(eval_source(expr), expected, "wrong result for '{}'", expr);
+    }
+}
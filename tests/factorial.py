@@ -0,0 +1,17 @@
+def factorial(n: u64) -> u64:
+  if n == 0:
+    return 1
+  var rest: u64 = factorial(n - 1)
+  return n * rest
+
+def factorial_loop(n: u64) -> u64:
+  var result: u64 = 1
+  while n > 0:
+    result = result * n
+    n = n - 1
+  return result
+
+print_int(factorial(0))
+print_int(factorial(5))
+print_int(factorial(20))
+print_int(factorial_loop(20))
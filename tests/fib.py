@@ -0,0 +1,16 @@
+def fib(n: u64) -> u64:
+  if n < 2:
+    return n
+  var a: u64 = fib(n - 1)
+  var b: u64 = fib(n - 2)
+  return a + b
+
+def fib_expr(n: u64) -> u64:
+  if n < 2:
+    return n
+  return fib_expr(n - 1) + fib_expr(n - 2)
+
+print_int(fib(1))
+print_int(fib(10))
+print_int(fib(20))
+print_int(fib_expr(20))
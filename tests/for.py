@@ -0,0 +1,53 @@
+# 0 1 2
+for i in range(3):
+  print_int(i)
+
+# 10 13 16 19
+for i in range(10, 20, 3):
+  print_int(i)
+
+# counts down: 5 4 3 2 1
+for i in range(5, 0, -1):
+  print_int(i)
+
+# i64 loop variable: -2 -1 0 1
+for i in range(-2, 2):
+  print_int(i)
+
+# empty range
+for i in range(4, 4):
+  print_int(999)
+
+# continue still runs the step, break leaves the loop: 0 1 3 5
+for i in range(10):
+  if i == 6:
+    break
+  if i == 2:
+    continue
+  if i == 4:
+    continue
+  print_int(i)
+
+def sum_to(n: u64) -> u64:
+  var total: u64 = 0
+  for i in range(1, n + 1):
+    total = total + i
+  return total
+
+print_int(sum_to(100))
+
+# counts down from an unsigned variable: 3 2 1, 9 6 3
+var n: u64 = 3
+for j in range(n, 0, -1):
+  print_int(j)
+var top: u8 = 9
+for j in range(top, 0, -3):
+  print_int(j)
+
+# the same inside a function, with a typed step: 4 2
+def down(from: u64) -> void:
+  var step: i64 = -2
+  for k in range(from, 0, step):
+    print_int(k)
+
+down(4)
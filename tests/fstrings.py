@@ -0,0 +1,18 @@
+# f-strings are printed piece by piece, nothing is allocated
+var x: u64 = 3
+var y: f64 = 3.14159
+var name = "blythia"
+print(f"x={x} y={y:.3}")
+print(f"y={y:.2f} {y:.0f} {y:.10f} {y} {{braces}} {x + 1} {x > 2} {name}")
+print("a", f"b{x}c", 2.5)
+print_str(f"{-y:.1f}|{y * 1000000.0:.2}|{2.5:.0f}|{0.5:.3f}")
+
+# without 'f' the precision counts significant digits, like python
+print(f"{y:.1} {y * 1000.0:.4} {3141590.0:.2} {3.0:.3} {0.00012345:.2} {0.000012345:.2} {-y:.0}")
+
+def half(v: f64) -> f64:
+  return v / 2.0
+
+print(f"{half(y):.4f} {len(name)}")
+print(f"")
+print(f'single {x}')
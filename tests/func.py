@@ -29,3 +29,12 @@ def test(a: u64, b: u64, c: u64, d: u64, e: u64, f: u64, g: u64, h: u64, i: u64)
   print_int(a + b + c + d + e + f + g + h + i)
 
 test(1,2,3,4,5,6,7,8,9)
+
+# a bare 'return' leaves a void function early
+def above(n: u64, limit: u64) -> void:
+  if n < limit:
+    return
+  print_int(n)
+
+above(3, 5)
+above(7, 5)
@@ -0,0 +1,24 @@
+# integer literals are u64, float literals f64: 3 18446744073709551615 7
+var a = 3
+print_int(a)
+var b = a - 4
+print_int(b)
+var c = 3.5 * 2.0
+print_int(u64(c))
+
+# other values keep their type: -2 1 0
+var d: i8 = -1
+var e = d * 2
+print_int(e)
+var f = a > 2
+print_int(u64(f))
+var g = 1 == 2
+print_int(u64(g))
+
+# from function calls and casts: 42 -1
+def answer() -> i32:
+  return 42
+var h = answer()
+print_int(h)
+var i = i16(65535)
+print_int(i)
@@ -0,0 +1,52 @@
+# wrap around at the width of the type: 0 255 -128 127
+var a: u8 = 255
+a = a + 1
+print_int(a)
+a = a - 1
+print_int(a)
+var b: i8 = 127
+b = b + 1
+print_int(b)
+b = b - 1
+print_int(b)
+
+# 65535 -32768 4294967295 -2147483648
+var c: u16 = 0
+c = c - 1
+print_int(c)
+var d: i16 = -32767
+d = d - 1
+print_int(d)
+var e: u32 = 0
+print_int(~e)
+var f: i32 = 2147483647
+print_int(f + 1)
+
+# signed arithmetic: -7 -3 -1 21
+var g: i64 = -7
+print_int(g)
+print_int(g / 2)
+print_int(g - g * 2 / 2 - 1)
+print_int(g * -3)
+
+# signed and unsigned compares: 1 0
+var h: i32 = -1
+if h < 0:
+  print_int(1)
+var big: u64 = 0 - 1
+if big < 0:
+  print_int(999)
+else:
+  print_int(0)
+
+# every width as parameter, local and return value: -1 200 -299 70000 -5 10
+def widths(p8: i8, q8: u8, p16: i16, q32: u32, p64: i64) -> i16:
+  var sum: i16 = p16 + 1
+  print_int(p8)
+  print_int(q8)
+  print_int(sum)
+  print_int(q32)
+  print_int(p64)
+  return sum + 309
+
+print_int(widths(-1, 200, -300, 70000, -5))
@@ -0,0 +1,19 @@
+# Python spelling of the boolean operators: 1 0 1 1
+var t: bool = true
+var f: bool = false
+print_int(u64(t and not f))
+print_int(u64(t and f))
+print_int(u64(f or t))
+print_int(u64(t && f || t))
+
+# 'and' binds tighter than 'or': 1 0
+print_int(u64(t or t and f))
+print_int(u64((t or t) and f))
+
+# 'is' compares bools: 1 0 1 1
+print_int(u64(t is true))
+print_int(u64(t is f))
+print_int(u64(t is not f))
+var x = 3
+if (x > 2) is not f:
+  print_int(1)
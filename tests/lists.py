@@ -0,0 +1,33 @@
+# list literals fill arrays, 'for x in a' goes over the elements
+var primes: u64[5] = [2, 3, 5, 7, 11]
+var total: u64 = 0
+for p in primes:
+  total += p
+print(total, len(primes))
+var temps = [1.5, 2, -3.25]
+for t in temps:
+  print(t)
+var small: i8[3] = [-1, 2, 127]
+print(small[0], small[2], len(small))
+var flags = [true, false]
+for f in flags:
+  if not f:
+    continue
+  print("flag", f)
+var names = ["ann", "bob"]
+for n in names:
+  print(n, len(n))
+def local_sum() -> i64:
+  var xs: i64[4] = [10, -20, 30, 40]
+  var s: i64 = 0
+  for x in xs:
+    if x == 40:
+      break
+    s += x
+  return s
+print(local_sum())
+var i: u64 = 0
+while i < len(primes):
+  primes[i] *= 2
+  i += 1
+print(f"{primes[4]} {len(primes)}")
@@ -0,0 +1,14 @@
+# locals without a value start at zero, even after an other call used the same stack slots
+def dirty(n: u64) -> u64:
+  var a: u64 = n
+  var b: u64 = n * 2
+  return a
+
+def clean() -> u64:
+  var a: u64
+  var b: u64
+  print_int(a)
+  return b
+
+print_int(dirty(12345))
+print_int(clean())
@@ -0,0 +1,74 @@
+# floor division and modulo round towards negative infinity: 3 1 -4 1 -4 -1 -3 -1
+print_int(7 // 2)
+print_int(7 % 2)
+var a: i64 = -7
+print_int(a // 2)
+print_int(a % 2)
+var b: i64 = 2
+print_int(-8 // b)
+print_int(7 % -b)
+print_int(a // b + 1)
+print_int(a % -2)
+
+# power: 1024 1 -8 9 -4 512 0 1 -1 0
+print_int(2 ** 10)
+print_int(5 ** 0)
+var c: i32 = -2
+print_int(c ** 3)
+print_int(c ** 2 + 5)
+print_int(-c ** 2)
+print_int(2 ** 3 ** 2)
+print_int(b ** -1)
+var one: i64 = 1
+print_int(one ** -5)
+print_int((one - 2) ** -3)
+var d: u8 = 2
+print_int(d ** 8)
+
+# bitwise: 8 14 6 255 48 3 -4 0
+print_int(12 & 10)
+print_int(12 | 10)
+print_int(12 ^ 10)
+print_int(~d ^ 2)
+print_int(3 << 4)
+print_int(48 >> 4)
+print_int(a >> 1)
+print_int(d << 7 << 1)
+
+# precedence like Python: 1 20 7 1
+print_int(1 | 2 & 4)
+print_int(2 + 3 << 2)
+print_int(1 + 2 * 3 % 4 ** 2)
+print_int(u64(1 + 1 == 2 & 3))
+
+# floats: 3 3 -4 3 1024 8 28 -8 0 1 5
+var x: f64 = 7.5
+print_int(i64(x // 2.0))
+print_int(i64(x % 2.0 * 2.0))
+print_int(i64(-x // 2.0))
+print_int(i64(-x % 3.0 * 2.0))
+print_int(i64(2.0 ** 10.0))
+print_int(i64(64.0 ** 0.5))
+print_int(i64(x ** 2 / 2.0 + 0.5))
+print_int(i64(-2.0 ** 3.0))
+print_int(i64(0.0 ** 3.0))
+print_int(i64(0.0 ** 0.0))
+print_int(i64((0.0 - 2.0) ** 2.0 + 1.0))
+
+# call arguments stay in place across 'div' and nested calls: 1 2 3 2, 12 5 34 0, 123456789
+def show(a: u64, b: u64, c: u64, d: u64) -> void:
+  print_int(a)
+  print_int(b)
+  print_int(c)
+  print_int(d)
+
+def join(a: u64, b: u64) -> u64:
+  return a * 10 + b
+
+def many(a: u64, b: u64, c: u64, d: u64, e: u64, f: u64, g: u64, h: u64, i: u64) -> void:
+  print_int(join(join(join(join(join(join(join(join(a, b), c), d), e), f), g), h), i))
+
+var y: u64 = 17
+show(1, 2, y // 5, y % 5)
+show(join(1, 2), 5, join(3, 4), 0)
+many(1, 2, 3, 4, 5, 6, 7, 8, 9)
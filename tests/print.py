@@ -0,0 +1,21 @@
+# typed prints
+var x: f64 = 2.5
+print_float(x)
+print_float(-0.125)
+print_float(3)
+print_float(1.0 / 3.0)
+print_float(12345678901234567890.0)
+print_float(-1.0 * 0.0)
+print_float(0.9999999)
+print_float(1.0 / 0.0)
+print_float(-1.0 / 0.0)
+print_bool(true)
+print_bool(1 < 0)
+print_int(42)
+
+# print takes any number of values and separates them with a space
+print(1, i64(-2), 2.5, true)
+print()
+var i: i8 = -5
+print(i, x * 2.0, i > 0)
+print(x)
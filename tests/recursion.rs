@@ -0,0 +1,66 @@
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use blythia::builder::Builder;
+use blythia::compiler::Compiler;
+use blythia::lexer::Lexer;
+use blythia::operations::{Operation, Program};
+use blythia::parser::Parser;
+use blythia::type_checker::TypeChecker;
+
+const PROGRAMS: [&str; 3] = ["fib", "factorial", "locals"];
+
+fn source_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join(format!("{name}.py"))
+}
+
+fn build(name: &str) -> Program {
+    let source = fs::read_to_string(source_path(name)).unwrap();
+    let file_name = format!("{name}.py");
+    let mut parser = Parser::new(Lexer::new(&source, file_name.clone()), file_name.clone());
+    let mut ast = parser.parse().unwrap();
+    TypeChecker::new(file_name.clone()).prepare_ast(&mut ast).unwrap();
+    Builder::new(file_name).build_program(&mut ast).unwrap()
+}
+
+// A recursive call must not overwrite the locals of its caller, so they live in the stack
+// frame and not in '.bss' like globals.
+#[test]
+fn function_locals_live_in_the_stack_frame() {
+    for name in PROGRAMS {
+        let program = build(name);
+        assert!(program.vars.is_empty(), "'{}' keeps locals in '.bss': {:?}", name, program.vars);
+        let frame_slot = |op: &Operation| matches!(op, Operation::StoreLocal(_, _) | Operation::LoadLocal(_, _));
+        assert!(program.function_defs.iter().any(frame_slot), "'{}' never uses a stack slot: {:#?}", name, program.function_defs);
+    }
+}
+
+// Runs the programs and compares what they print with the '.expected' file next to them.
+// Needs nasm and ld, like the compiler itself.
+#[test]
+fn recursive_programs_print_the_expected_output() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    for name in PROGRAMS {
+        let asm = dir.join(format!("{name}.asm"));
+        let object = asm.with_extension("o");
+        let binary = asm.with_extension("");
+        fs::write(&asm, Compiler::compile_program(build(name))).unwrap();
+
+        let nasm = match Command::new("nasm").arg("-felf64").arg(&asm).output() {
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                eprintln!("nasm is not installed, the output of the programs is not checked");
+                return;
+            }
+            nasm => nasm.unwrap(),
+        };
+        assert!(nasm.status.success(), "nasm failed for '{}': {}", name, String::from_utf8_lossy(&nasm.stderr));
+        let ld = Command::new("ld").arg("-o").arg(&binary).arg(&object).output().unwrap();
+        assert!(ld.status.success(), "ld failed for '{}': {}", name, String::from_utf8_lossy(&ld.stderr));
+
+        let output = Command::new(&binary).output().unwrap();
+        let expected = fs::read_to_string(source_path(name).with_extension("expected")).unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), expected, "output of '{}' does not match", name);
+    }
+}
@@ -0,0 +1,23 @@
+# every call prints its number, so skipped calls leave no trace
+def check(n: u64, result: bool) -> bool:
+  print_int(n)
+  return result
+
+# the right side only runs when the left one does not decide: 1 2 0 3 0 4 5 1 6 1
+var a: bool = check(1, true) and check(2, false)
+print_int(u64(a))
+a = check(3, false) and check(99, true)
+print_int(u64(a))
+a = check(4, false) or check(5, true)
+print_int(u64(a))
+a = check(6, true) or check(99, false)
+print_int(u64(a))
+
+# in conditions: 7 8 9 10 11 20 21
+if check(7, true) && (check(8, false) || check(9, true)):
+  print_int(10)
+if check(11, false) and check(99, true):
+  print_int(99)
+var i: u64 = 0
+while i < 3 and check(i + 20, i != 1):
+  i = i + 1
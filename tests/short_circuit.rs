@@ -0,0 +1,41 @@
+use blythia::builder::Builder;
+use blythia::lexer::Lexer;
+use blythia::operations::{Operation, Program};
+use blythia::parser::Parser;
+use blythia::type_checker::TypeChecker;
+
+fn build(source: &str) -> Program {
+    let file_name = "short_circuit.py".to_string();
+    let mut parser = Parser::new(Lexer::new(source, file_name.clone()), file_name.clone());
+    let mut ast = parser.parse().unwrap();
+    TypeChecker::new(file_name.clone()).prepare_ast(&mut ast).unwrap();
+    Builder::new(file_name).build_program(&mut ast).unwrap()
+}
+
+fn position(program: &Program, name: &str, found: impl Fn(&Operation) -> bool) -> usize {
+    program.main.iter().position(found).unwrap_or_else(|| panic!("no {} in {:#?}", name, program.main))
+}
+
+// The call on the right has to sit between the jump over it and its label, so it only
+// runs when the left side does not decide the result.
+fn assert_right_side_is_skippable(op: &str) {
+    let source = format!("def left() -> bool:\n  return true\ndef right() -> bool:\n  return false\nvar a: bool = left() {op} right()\n");
+    let program = build(&source);
+    let left = position(&program, "call of left", |o| matches!(o, Operation::FunctionCall(name, _) if name == "left"));
+    let right = position(&program, "call of right", |o| matches!(o, Operation::FunctionCall(name, _) if name == "right"));
+    let jump = position(&program, "short circuit", |o| matches!(o, Operation::ShortCircuitAnd(_) | Operation::ShortCircuitOr(_)));
+    let end = position(&program, "end of short circuit", |o| matches!(o, Operation::EndShortCircuit(_)));
+    assert!(left < jump && jump < right && right < end, "'{}' evaluates the right side unconditionally: {:#?}", op, program.main);
+}
+
+#[test]
+fn and_skips_the_right_side() {
+    assert_right_side_is_skippable("and");
+    assert_right_side_is_skippable("&&");
+}
+
+#[test]
+fn or_skips_the_right_side() {
+    assert_right_side_is_skippable("or");
+    assert_right_side_is_skippable("||");
+}
@@ -0,0 +1,22 @@
+# string literals live in the data segment, 'len' counts their bytes
+var s: str = "hello"
+print_str(s)
+print(s, 'world', len(s))
+var t = "tab\there \"quoted\" 'single' back\\slash"
+print(t)
+print(len(""), len(t))
+var e: str = ''
+print_str(e)
+
+def greet(name: str) -> u64:
+  print("hi", name)
+  return len(name)
+
+print(greet("bob") + 1)
+
+def longest(a: str, b: str) -> str:
+  return a if len(a) > len(b) else b
+
+print(longest("ab", "abc"))
+s = "reassigned"
+print(s, len(s))
@@ -0,0 +1,42 @@
+use std::fs;
+use std::path::Path;
+
+use blythia::lexer::Lexer;
+use blythia::parser::Parser;
+use blythia::type_checker::TypeChecker;
+
+// Every '.py' file in tests/errors is a broken program. The '.expected' file next to it
+// lists the diagnostics the parser has to report for it, one per line. A program that parses
+// has to be rejected by the type checker with the single diagnostic in its '.expected' file.
+#[test]
+fn syntax_errors() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("errors");
+    let mut files: Vec<_> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "py"))
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "no test files found in {:?}", dir);
+
+    for file in files {
+        let source = fs::read_to_string(&file).unwrap();
+        let file_name = file.file_name().unwrap().to_str().unwrap().to_string();
+        let lexer = Lexer::new(&source, file_name.clone());
+        let mut parser = Parser::new(lexer, file_name.clone());
+        let diagnostics = match parser.parse() {
+            Err(diagnostics) => diagnostics,
+            Ok(mut ast) => {
+                let Err(diagnostic) = TypeChecker::new(file_name.clone()).prepare_ast(&mut ast) else {
+                    panic!("'{}' was checked without any errors", file_name);
+                };
+                vec![diagnostic]
+            }
+        };
+
+        let found: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+        let expected = fs::read_to_string(file.with_extension("expected")).unwrap();
+        let expected: Vec<&str> = expected.lines().collect();
+        assert_eq!(found, expected, "diagnostics of '{}' do not match", file_name);
+    }
+}
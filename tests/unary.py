@@ -0,0 +1,15 @@
+var x: u64 = 0
+print_int(~x)
+print_int(~~5 + 1)
+
+var flag: bool = false
+if not flag:
+  print_int(1)
+if not flag && flag:
+  print_int(999)
+if not x == 1:
+  print_int(2)
+
+var f: f64 = -2.5
+var g: f64 = 3.0 * -f
+var h: f64 = --g